@@ -0,0 +1,49 @@
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use tableflip_rust::Parser;
+
+/// Generates an ASCII table with the given number of rows
+fn ascii_table(rows: usize) -> String {
+    let mut input = String::new();
+    for row in 0..rows {
+        for col in 0..8 {
+            input.push_str(&format!("\"cell {} {}\" ", row, col));
+        }
+        input.push('\n');
+    }
+    input
+}
+
+/// Generates a single row with the given number of cells
+fn long_row(cells: usize) -> String {
+    let mut input = String::new();
+    for col in 0..cells {
+        input.push_str(&format!("\"cell {}\" ", col));
+    }
+    input
+}
+
+fn parse(c: &mut Criterion) {
+    let input = ascii_table(10_000);
+    let mut group = c.benchmark_group("parse");
+    group.throughput(Throughput::Bytes(input.len() as u64));
+    group.bench_function("ascii", |b| {
+        b.iter(|| Parser::new(black_box(&input)).count())
+    });
+    group.finish();
+}
+
+/// The throughput stays the same as the row grows if parsing is linear
+fn parse_long_row(c: &mut Criterion) {
+    let mut group = c.benchmark_group("parse_long_row");
+    for cells in [1_000, 10_000, 100_000] {
+        let input = long_row(cells);
+        group.throughput(Throughput::Bytes(input.len() as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(cells), &input, |b, input| {
+            b.iter(|| Parser::new(black_box(input)).comments(true).count())
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, parse, parse_long_row);
+criterion_main!(benches);
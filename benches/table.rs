@@ -0,0 +1,37 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion, Throughput};
+use tableflip_rust::{Parser, Rows, Table};
+
+/// Generates an ASCII table with the given number of rows
+fn ascii_table(rows: usize) -> String {
+    let mut input = String::new();
+    for row in 0..rows {
+        for col in 0..8 {
+            input.push_str(&format!("\"cell {} {}\" ", row, col));
+        }
+        input.push('\n');
+    }
+    input
+}
+
+fn build(input: &str) -> Table<'_> {
+    let parser = Parser::new(input).map(Result::unwrap);
+    let mut head = Rows::new(parser).head();
+    let lines = input.bytes().filter(|&b| b == b'\n').count();
+    let mut table = Table::new().head(head.by_ref()).reserve(lines);
+    let mut tail = head.into_tail();
+    while let Some(row) = tail.row() {
+        table = table.tail(row);
+    }
+    table
+}
+
+fn table(c: &mut Criterion) {
+    let input = ascii_table(10_000);
+    let mut group = c.benchmark_group("table");
+    group.throughput(Throughput::Bytes(input.len() as u64));
+    group.bench_function("build", |b| b.iter(|| build(black_box(&input))));
+    group.finish();
+}
+
+criterion_group!(benches, table);
+criterion_main!(benches);
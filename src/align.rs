@@ -0,0 +1,8 @@
+/// The horizontal alignment of cells in a column
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub enum Align {
+    #[default]
+    Left,
+    Right,
+    Center,
+}
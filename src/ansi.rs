@@ -0,0 +1,107 @@
+use std::fmt;
+
+/// The ANSI terminal color
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Color {
+    Black,
+    Red,
+    Green,
+    Yellow,
+    Blue,
+    Magenta,
+    Cyan,
+    White,
+}
+
+/// The text style made of ANSI SGR attributes
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub struct Ansi {
+    bold: bool,
+    inverse: bool,
+    color: Option<Color>,
+}
+
+impl Ansi {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn bold(mut self) -> Self {
+        self.bold = true;
+        self
+    }
+
+    /// Swaps the foreground and background colors
+    pub fn inverse(mut self) -> Self {
+        self.inverse = true;
+        self
+    }
+
+    pub fn color(mut self, color: Color) -> Self {
+        self.color = Some(color);
+        self
+    }
+
+    /// Wraps the text in the escape codes
+    pub(crate) fn paint(self, text: &str) -> String {
+        if self == Self::default() {
+            return text.to_owned();
+        }
+
+        format!("{}{}\x1b[0m", self, text)
+    }
+}
+
+impl fmt::Display for Ansi {
+    /// Writes the escape code that enables the style
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let codes = self
+            .bold
+            .then_some(1)
+            .into_iter()
+            .chain(self.inverse.then_some(7))
+            .chain(self.color.map(|color| 30 + color as u8));
+
+        write!(f, "\x1b[")?;
+        for (idx, code) in codes.enumerate() {
+            if idx != 0 {
+                write!(f, ";")?;
+            }
+
+            write!(f, "{}", code)?;
+        }
+        write!(f, "m")
+    }
+}
+
+/// Iterates over chars of the text skipping ANSI escape sequences
+pub(crate) fn visible_chars(text: &str) -> impl Iterator<Item = char> + '_ {
+    let mut chars = text.chars();
+    std::iter::from_fn(move || loop {
+        match chars.next()? {
+            '\x1b' if chars.as_str().starts_with('[') => {
+                // The sequence ends with a char in the range `@`..=`~`
+                chars.by_ref().skip(1).find(|ch| ('@'..='~').contains(ch));
+            }
+            ch => return Some(ch),
+        }
+    })
+}
+
+/// Returns the width of the text in chars without escape sequences
+pub(crate) fn display_width(text: &str) -> usize {
+    visible_chars(text).count()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn paint() {
+        let ansi = Ansi::new().bold().color(Color::Red);
+        assert_eq!(ansi.paint("hi"), "\x1b[1;31mhi\x1b[0m");
+        assert_eq!(Ansi::new().paint("hi"), "hi");
+        assert_eq!(display_width(&ansi.paint("héllo")), 5);
+    }
+}
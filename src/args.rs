@@ -0,0 +1,312 @@
+use tableflip_rust::{Align, Color, Format, Style};
+
+/// The syntax of input files
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub enum InputFormat {
+    /// Cells in quotes
+    #[default]
+    Quoted,
+    Csv,
+    Tsv,
+    Markdown,
+    /// Detected from the first line
+    Auto,
+}
+
+/// Command line arguments
+#[derive(Debug, Default, Eq, PartialEq)]
+pub struct Args {
+    /// Input files, `-` or no files read stdin.
+    /// Each file starts with its own header, headers after the first one
+    /// are skipped but must have the same number of columns.
+    pub inputs: Vec<String>,
+    /// The output file, stdout if not set
+    pub output: Option<String>,
+    pub input_format: InputFormat,
+    pub format: Format,
+    pub style: Style,
+    /// Column names for input without a header.
+    /// If set, the first row of each input is data.
+    pub headers: Option<Vec<String>>,
+    /// The width limit of all columns
+    pub max_width: Option<usize>,
+    /// Width limits of columns paired with column indices
+    pub max_col_widths: Vec<(usize, usize)>,
+    /// A single alignment for all columns or one per column
+    pub align: Vec<Align>,
+    /// Colors of data columns paired with column indices
+    pub col_colors: Vec<(usize, Color)>,
+    /// Read input files twice instead of keeping them in memory
+    pub stream: bool,
+    /// Warn about cells that look like merged cells
+    pub lint: bool,
+    /// Blank lines separate tables, each with its own header
+    pub split: bool,
+    /// Truncate columns wider than the terminal or `max_width` with a warning
+    pub clamp: bool,
+    /// Wrap the text format in a fenced code block
+    pub code_fence: bool,
+    /// Parse lines starting with `#` as comments and print them above the table,
+    /// only for quoted input
+    pub comments: bool,
+}
+
+impl Args {
+    pub fn parse<A>(args: A) -> Result<Self, String>
+    where
+        A: IntoIterator<Item = String>,
+    {
+        let mut parsed = Self::default();
+        let mut plain = false;
+        let mut style = None;
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            // Split `--flag=value` into the flag and its value
+            let (flag, value) = match arg.find('=') {
+                Some(idx) if arg.starts_with("--") => {
+                    (&arg[..idx], Some(arg[idx + 1..].to_owned()))
+                }
+                _ => (arg.as_str(), None),
+            };
+
+            let value = || {
+                value
+                    .or_else(|| args.next())
+                    .ok_or_else(|| format!("{} requires a value", flag))
+            };
+
+            match flag {
+                "-o" | "--output" => parsed.output = Some(value()?),
+                "--format" => {
+                    let format = value()?;
+                    plain = format == "plain";
+                    parsed.format = match format.as_str() {
+                        "md" | "plain" => Format::Text,
+                        "html" => Format::Html,
+                        "csv" => Format::Csv,
+                        "json" => Format::Json,
+                        "jira" => Format::Jira,
+                        format => {
+                            return Err(format!(
+                                "unknown format {}, expected md, html, csv, json, jira or plain",
+                                format
+                            ))
+                        }
+                    }
+                }
+                "--input-format" => {
+                    parsed.input_format = match value()?.as_str() {
+                        "quoted" => InputFormat::Quoted,
+                        "csv" => InputFormat::Csv,
+                        "tsv" => InputFormat::Tsv,
+                        "md" => InputFormat::Markdown,
+                        "auto" => InputFormat::Auto,
+                        format => {
+                            return Err(format!(
+                                "unknown input format {}, expected quoted, csv, tsv, md or auto",
+                                format
+                            ))
+                        }
+                    }
+                }
+                "--style" => {
+                    style = match value()?.as_str() {
+                        "markdown" => Style::Markdown,
+                        "unicode" => Style::Unicode,
+                        "rounded" => Style::Rounded,
+                        "plain" => Style::Plain,
+                        style => {
+                            return Err(format!(
+                                "unknown style {}, expected markdown, unicode, rounded or plain",
+                                style
+                            ))
+                        }
+                    }
+                    .into()
+                }
+                "--headers" => {
+                    parsed.headers = Some(value()?.split(',').map(str::to_owned).collect())
+                }
+                "--max-width" => parsed.max_width = Some(parse_number(&value()?)?),
+                "--max-col-width" => {
+                    parsed.max_col_widths = value()?
+                        .split(',')
+                        .map(|spec| {
+                            let (col, width) = spec.split_once(':').ok_or_else(|| {
+                                format!("invalid column width {}, expected COL:WIDTH", spec)
+                            })?;
+
+                            Ok((parse_number(col)?, parse_number(width)?))
+                        })
+                        .collect::<Result<_, String>>()?
+                }
+                "--align" => {
+                    parsed.align = value()?
+                        .split(',')
+                        .map(|align| match align {
+                            "l" | "left" => Ok(Align::Left),
+                            "r" | "right" => Ok(Align::Right),
+                            "c" | "center" => Ok(Align::Center),
+                            _ => Err(format!(
+                                "unknown alignment {}, expected left, right or center",
+                                align
+                            )),
+                        })
+                        .collect::<Result<_, _>>()?
+                }
+                "--col-color" => {
+                    parsed.col_colors = value()?
+                        .split(',')
+                        .map(|spec| {
+                            let (col, color) = spec.split_once(':').ok_or_else(|| {
+                                format!("invalid column color {}, expected COL:COLOR", spec)
+                            })?;
+
+                            Ok((parse_number(col)?, parse_color(color)?))
+                        })
+                        .collect::<Result<_, String>>()?
+                }
+                "--stream" => parsed.stream = true,
+                "--lint" => parsed.lint = true,
+                "--split" => parsed.split = true,
+                "--clamp" => parsed.clamp = true,
+                "--code-fence" => parsed.code_fence = true,
+                "--comments" => parsed.comments = true,
+                "-" => parsed.inputs.push(arg),
+                _ if flag.starts_with('-') => return Err(format!("unknown flag {}", flag)),
+                _ => parsed.inputs.push(arg),
+            }
+        }
+
+        // The plain format is the text format in the plain style
+        parsed.style = match (plain, style) {
+            (true, Some(_)) => return Err("--format plain can't be used with --style".to_owned()),
+            (true, None) => Style::Plain,
+            (false, style) => style.unwrap_or_default(),
+        };
+
+        // Other syntaxes read `#` as a cell
+        if parsed.comments && parsed.input_format != InputFormat::Quoted {
+            return Err("--comments requires --input-format quoted".to_owned());
+        }
+
+        Ok(parsed)
+    }
+}
+
+fn parse_number(value: &str) -> Result<usize, String> {
+    value
+        .parse()
+        .map_err(|_| format!("invalid number {}", value))
+}
+
+fn parse_color(value: &str) -> Result<Color, String> {
+    match value {
+        "black" => Ok(Color::Black),
+        "red" => Ok(Color::Red),
+        "green" => Ok(Color::Green),
+        "yellow" => Ok(Color::Yellow),
+        "blue" => Ok(Color::Blue),
+        "magenta" => Ok(Color::Magenta),
+        "cyan" => Ok(Color::Cyan),
+        "white" => Ok(Color::White),
+        _ => Err(format!("unknown color {}", value)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<Args, String> {
+        Args::parse(args.iter().map(|&arg| arg.to_owned()))
+    }
+
+    #[test]
+    fn parse_args() {
+        assert_eq!(parse(&[]), Ok(Args::default()));
+        assert_eq!(
+            parse(&["in.txt", "-o", "out.md"]),
+            Ok(Args {
+                inputs: vec!["in.txt".to_owned()],
+                output: Some("out.md".to_owned()),
+                ..Args::default()
+            })
+        );
+        assert_eq!(
+            parse(&["--output=out.md", "a.txt", "-", "--format", "csv"]),
+            Ok(Args {
+                inputs: vec!["a.txt".to_owned(), "-".to_owned()],
+                output: Some("out.md".to_owned()),
+                format: Format::Csv,
+                ..Args::default()
+            })
+        );
+        assert_eq!(
+            parse(&["--style", "unicode"]).map(|args| args.style),
+            Ok(Style::Unicode)
+        );
+        assert_eq!(
+            parse(&["--style", "rounded"]).map(|args| args.style),
+            Ok(Style::Rounded)
+        );
+        assert_eq!(
+            parse(&["--format", "plain"]).map(|args| (args.format, args.style)),
+            Ok((Format::Text, Style::Plain))
+        );
+        assert!(parse(&["--style", "unicode", "--format", "plain"]).is_err());
+        assert!(parse(&["--format", "plain", "--style", "unicode"]).is_err());
+        assert_eq!(
+            parse(&["--format", "md", "--style", "unicode"]).map(|args| args.style),
+            Ok(Style::Unicode)
+        );
+        assert_eq!(
+            parse(&["--headers", "A,B"]).map(|args| args.headers),
+            Ok(Some(vec!["A".to_owned(), "B".to_owned()]))
+        );
+        assert_eq!(
+            parse(&["--max-width", "10", "--max-col-width", "0:5,2:20"])
+                .map(|args| (args.max_width, args.max_col_widths)),
+            Ok((Some(10), vec![(0, 5), (2, 20)]))
+        );
+        assert_eq!(
+            parse(&["--align", "right"]).map(|args| args.align),
+            Ok(vec![Align::Right])
+        );
+        assert_eq!(
+            parse(&["--align=l,r,center"]).map(|args| args.align),
+            Ok(vec![Align::Left, Align::Right, Align::Center])
+        );
+        assert_eq!(
+            parse(&["--col-color", "1:red,3:cyan"]).map(|args| args.col_colors),
+            Ok(vec![(1, Color::Red), (3, Color::Cyan)])
+        );
+        assert_eq!(
+            parse(&["--stream", "a.txt"]).map(|args| args.stream),
+            Ok(true)
+        );
+        assert_eq!(parse(&["--lint"]).map(|args| args.lint), Ok(true));
+        assert_eq!(
+            parse(&["--input-format=auto"]).map(|args| args.input_format),
+            Ok(InputFormat::Auto)
+        );
+        assert_eq!(parse(&["--split"]).map(|args| args.split), Ok(true));
+        assert_eq!(parse(&["--clamp"]).map(|args| args.clamp), Ok(true));
+        assert_eq!(
+            parse(&["--code-fence"]).map(|args| args.code_fence),
+            Ok(true)
+        );
+        assert_eq!(parse(&["--comments"]).map(|args| args.comments), Ok(true));
+        assert!(parse(&["--comments", "--input-format", "csv"]).is_err());
+        assert!(parse(&["--input-format=auto", "--comments"]).is_err());
+        assert!(parse(&["-o"]).is_err());
+        assert!(parse(&["--unknown"]).is_err());
+        assert!(parse(&["--format", "xml"]).is_err());
+        assert!(parse(&["--input-format", "xml"]).is_err());
+        assert!(parse(&["--align", "l,x"]).is_err());
+        assert!(parse(&["--style", "ascii"]).is_err());
+        assert!(parse(&["--max-width", "-1"]).is_err());
+        assert!(parse(&["--max-col-width", "0=5"]).is_err());
+        assert!(parse(&["--col-color", "0:pink"]).is_err());
+    }
+}
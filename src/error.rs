@@ -0,0 +1,140 @@
+use std::{error::Error, fmt};
+
+/// The error returned when a row or column index is out of range
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct OutOfRange {
+    pub index: usize,
+    pub len: usize,
+}
+
+impl fmt::Display for OutOfRange {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "index {} is out of range for length {}",
+            self.index, self.len
+        )
+    }
+}
+
+impl Error for OutOfRange {}
+
+/// The error returned when the number of provided items
+/// doesn't match the number of columns
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct CountMismatch {
+    pub expected: usize,
+    pub provided: usize,
+}
+
+impl fmt::Display for CountMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "expected {} columns, provided {}",
+            self.expected, self.provided
+        )
+    }
+}
+
+impl Error for CountMismatch {}
+
+/// The error returned when a data row has a wrong number of cells
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RowLenError {
+    /// The index the data row would have in the table, counted from 0 without the header
+    pub row: usize,
+    pub expected: usize,
+    pub provided: usize,
+    /// Cells of the row
+    pub cells: Vec<String>,
+}
+
+impl fmt::Display for RowLenError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "row {} has {} cells, expected {}: {:?}",
+            self.row, self.provided, self.expected, self.cells
+        )
+    }
+}
+
+impl Error for RowLenError {}
+
+/// The error of parsing the input, positions are byte offsets in the input
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum ParseError {
+    /// The quote has no partner
+    UnmatchedQuote(usize),
+    /// The char can't be here
+    UnexpectedChar(usize),
+    /// The separator isn't followed by a cell
+    MissingCell(usize),
+    /// The cell bounds aren't on char boundaries
+    BadSlice(usize),
+}
+
+impl ParseError {
+    /// Returns the position of the error
+    pub fn position(self) -> usize {
+        match self {
+            Self::UnmatchedQuote(at)
+            | Self::UnexpectedChar(at)
+            | Self::MissingCell(at)
+            | Self::BadSlice(at) => at,
+        }
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::UnmatchedQuote(at) => write!(f, "unmatched quote at {}", at),
+            Self::UnexpectedChar(at) => write!(f, "unexpected char at {}", at),
+            Self::MissingCell(at) => write!(f, "missing cell after the separator at {}", at),
+            Self::BadSlice(at) => write!(f, "cell bounds off char boundaries at {}", at),
+        }
+    }
+}
+
+impl Error for ParseError {}
+
+/// The error returned when the input isn't a rectangular table
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum RaggedError {
+    /// The input can't be parsed
+    Parse(ParseError),
+    /// The data row has a different number of cells than the header
+    Row {
+        row: usize,
+        expected: usize,
+        provided: usize,
+    },
+}
+
+impl fmt::Display for RaggedError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Parse(err) => err.fmt(f),
+            Self::Row {
+                row,
+                expected,
+                provided,
+            } => write!(
+                f,
+                "row {} has {} cells, expected {}",
+                row, provided, expected
+            ),
+        }
+    }
+}
+
+impl Error for RaggedError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::Parse(err) => Some(err),
+            Self::Row { .. } => None,
+        }
+    }
+}
@@ -0,0 +1,13 @@
+/// The output format of a table
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub enum Format {
+    /// Aligned text drawn in the table style
+    #[default]
+    Text,
+    Html,
+    Csv,
+    /// The JSON object with `header` and `rows` arrays
+    Json,
+    /// Jira and Confluence wiki markup, header cells are delimited by `||`
+    Jira,
+}
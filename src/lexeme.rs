@@ -1,5 +1,25 @@
-#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+use std::borrow::Cow;
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Lexeme<'a> {
-    Cell(&'a str),
+    Cell(#[cfg_attr(feature = "serde", serde(borrow))] Cow<'a, str>),
     NewLine,
+    /// The text of a comment line after the comment prefix
+    Comment(#[cfg_attr(feature = "serde", serde(borrow))] Cow<'a, str>),
+}
+
+#[cfg(all(test, feature = "serde"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn serialize() {
+        let lexemes = [Lexeme::Cell("hi".into()), Lexeme::NewLine];
+        let json = serde_json::to_string(&lexemes).unwrap();
+        assert_eq!(json, r#"[{"Cell":"hi"},"NewLine"]"#);
+
+        let parsed: Vec<Lexeme> = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed, lexemes);
+    }
 }
@@ -0,0 +1,181 @@
+mod align;
+mod ansi;
+mod error;
+mod format;
+mod lexeme;
+mod parser;
+#[cfg(feature = "csv")]
+mod reader;
+mod rows;
+mod style;
+mod table;
+
+#[cfg(feature = "csv")]
+pub use crate::reader::CsvReader;
+
+pub use crate::{
+    align::Align,
+    ansi::{Ansi, Color},
+    error::{CountMismatch, OutOfRange, ParseError, RaggedError, RowLenError},
+    format::Format,
+    lexeme::Lexeme,
+    parser::{Location, Parser, Terminator},
+    rows::{Head, Rows, Tail, TailRow},
+    style::Style,
+    table::{Stream, Table, TableWriter},
+};
+
+/// Runs `f` over rows of the quoted input, the input is parsed up to the first error
+fn parse_rows<'a, F, T>(input: &'a str, f: F) -> Result<T, ParseError>
+where
+    F: FnOnce(Rows<&mut dyn Iterator<Item = Lexeme<'a>>>) -> T,
+{
+    let mut error = None;
+    let out = {
+        let mut lexemes =
+            Parser::new(input).map_while(|res| res.map_err(|err| error = Some(err)).ok());
+
+        f(Rows::new(&mut lexemes as &mut dyn Iterator<Item = _>))
+    };
+
+    match error {
+        Some(err) => Err(err),
+        None => Ok(out),
+    }
+}
+
+/// Parses the quoted input into the header row followed by data rows.
+/// Data rows are padded with empty cells or truncated to the header width.
+pub fn parse_table(input: &str) -> Result<Vec<Vec<String>>, ParseError> {
+    parse_rows(input, |rows| {
+        let mut head = rows.head();
+        let mut rows = vec![head.by_ref().map(String::from).collect()];
+        let mut tail = head.into_tail();
+        while let Some(row) = tail.row() {
+            rows.push(row.map(String::from).collect());
+        }
+
+        rows
+    })
+}
+
+/// Counts data rows and columns of the quoted input without storing cells,
+/// the counts are the same as of [`parse_table`] without the header row
+pub fn count_table(input: &str) -> Result<(usize, usize), ParseError> {
+    parse_rows(input, |rows| {
+        let mut head = rows.head();
+        let cols = head.by_ref().count();
+        let mut tail = head.into_tail();
+        let mut rows = 0;
+        while let Some(row) = tail.row() {
+            row.for_each(drop);
+            rows += 1;
+        }
+
+        (rows, cols)
+    })
+}
+
+/// Checks every data row of the quoted input has as many cells as the header,
+/// the error holds the index of the first data row that doesn't. Blank lines are skipped.
+pub fn validate_rectangular(input: &str) -> Result<(), RaggedError> {
+    let res = parse_rows(input, |rows| {
+        let mut head = rows.skip_blank_rows(true).head();
+        let expected = head.by_ref().count();
+        let mut tail = head.into_tail();
+        let mut row = 0;
+        while let Some(provided) = tail.row_len() {
+            if provided != expected {
+                return Err(RaggedError::Row {
+                    row,
+                    expected,
+                    provided,
+                });
+            }
+
+            row += 1;
+        }
+
+        Ok(())
+    });
+
+    res.map_err(RaggedError::Parse)?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_table() {
+        let input = "\"a\" \"b\" \"c\"\n\"0\" \"1\"\n\"2\" \"3\" \"4\" \"5\"\n\n";
+        assert_eq!(
+            super::parse_table(input).unwrap(),
+            [
+                ["a", "b", "c"],
+                ["0", "1", ""],
+                ["2", "3", "4"],
+                ["", "", ""],
+            ]
+        );
+
+        assert_eq!(
+            super::parse_table("\n\"a\"\n").unwrap(),
+            [Vec::<String>::new(), vec![]]
+        );
+
+        assert_eq!(
+            super::parse_table("\"a\" b"),
+            Err(ParseError::UnexpectedChar(4))
+        );
+    }
+
+    #[test]
+    fn count_table() {
+        let input = "\"a\" \"b\" \"c\"\n\"0\" \"1\"\n\"2\" \"3\" \"4\" \"5\"\n\n";
+        assert_eq!(super::count_table(input), Ok((3, 3)));
+        assert_eq!(super::count_table(""), Ok((0, 0)));
+        assert_eq!(super::count_table("\n\"a\"\n"), Ok((1, 0)));
+        assert_eq!(
+            super::count_table("\"a\" b"),
+            Err(ParseError::UnexpectedChar(4))
+        );
+    }
+
+    #[test]
+    fn validate_rectangular() {
+        let input = "\"a\" \"b\"\n\"0\" \"1\"\n\"2\" \"3\"\n";
+        assert_eq!(super::validate_rectangular(input), Ok(()));
+
+        let input = "\"a\" \"b\"\n\"0\" \"1\"\n\"2\"\n\"3\" \"4\" \"5\"";
+        assert_eq!(
+            super::validate_rectangular(input),
+            Err(RaggedError::Row {
+                row: 1,
+                expected: 2,
+                provided: 1,
+            })
+        );
+
+        let input = "\"a\" \"b\"\n\"0\" \"1\"\n\n";
+        assert_eq!(super::validate_rectangular(input), Ok(()));
+
+        let input = "\n\n\"a\" \"b\"\n\"0\" \"1\"\n";
+        assert_eq!(super::validate_rectangular(input), Ok(()));
+
+        let input = "\n\n\"a\" \"b\"\n\"1\"\n";
+        assert_eq!(
+            super::validate_rectangular(input),
+            Err(RaggedError::Row {
+                row: 0,
+                expected: 2,
+                provided: 1,
+            })
+        );
+
+        assert_eq!(
+            super::validate_rectangular("\"a\" b"),
+            Err(RaggedError::Parse(ParseError::UnexpectedChar(4)))
+        );
+    }
+}
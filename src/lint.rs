@@ -0,0 +1,73 @@
+//! Heuristics for cells that likely merge several cells because of a missing quote.
+//!
+//! A cell is flagged if, not counting its leading and trailing whitespace, it has
+//! - a line break, since a quote left open swallows the line end,
+//! - a run of three or more whitespace chars, like the gap between two cells,
+//! - a quote next to whitespace, like `a" "b` left from a closing and an opening quote.
+//!
+//! These are guesses, so warnings never fail the run.
+
+use tableflip_rust::{Lexeme, Location};
+
+/// The shortest whitespace run that looks like a gap between cells
+const GAP: usize = 3;
+
+/// Returns why the cell looks like merged cells
+fn check(cell: &str) -> Option<&'static str> {
+    let cell = cell.trim();
+    if cell.contains('\n') {
+        return Some("line break inside the cell");
+    }
+
+    let mut run = 0;
+    for ch in cell.chars() {
+        run = if ch.is_whitespace() { run + 1 } else { 0 };
+        if run == GAP {
+            return Some("long whitespace run inside the cell");
+        }
+    }
+
+    let quote = cell
+        .chars()
+        .zip(cell.chars().skip(1))
+        .any(|(prev, ch)| prev == '"' && ch.is_whitespace() || prev.is_whitespace() && ch == '"');
+
+    if quote {
+        return Some("quote next to whitespace inside the cell");
+    }
+
+    None
+}
+
+/// Prints a warning to stderr if the cell of the input at the position looks like merged cells
+pub fn warn(path: &str, input: &str, lexeme: &Lexeme, at: usize) {
+    let cell = match lexeme {
+        Lexeme::Cell(cell) => cell,
+        Lexeme::NewLine | Lexeme::Comment(_) => return,
+    };
+
+    if let Some(reason) = check(cell) {
+        let loc = Location::new(input, at);
+        eprintln!("{}:{}:{}: warning: {}", path, loc.line, loc.col, reason);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn check_cells() {
+        assert_eq!(check("  name  "), None);
+        assert_eq!(check("first name"), None);
+        assert_eq!(
+            check("a\" \"b"),
+            Some("quote next to whitespace inside the cell")
+        );
+        assert_eq!(
+            check("name    age"),
+            Some("long whitespace run inside the cell")
+        );
+        assert_eq!(check("name\nage"), Some("line break inside the cell"));
+    }
+}
@@ -1,42 +1,283 @@
-mod lexeme;
-mod parser;
-mod rows;
-mod table;
-
-pub use lexeme::Lexeme;
-use parser::Parser;
-use rows::Rows;
+mod args;
+mod lint;
+mod stream;
+
+use args::{Args, InputFormat};
 use std::{
-    io::{self, Read},
+    env,
+    fs::{self, File},
+    io::{self, BufWriter, IsTerminal, Read, Write},
+    iter,
     process::exit,
 };
-use table::Table;
+use tableflip_rust::{Ansi, Lexeme, Location, ParseError, Parser, Rows, Table};
+
+/// The terminal width if `COLUMNS` isn't set
+const TERMINAL_WIDTH: usize = 80;
+
+fn args_error(message: &str) -> ! {
+    eprintln!("{}", message);
+    exit(1);
+}
+
+fn parse_error(path: &str, loc: Location, err: ParseError) -> ! {
+    eprintln!("{}:{}:{}: {}", path, loc.line, loc.col, err);
+    eprintln!("{}", loc.text);
+
+    // Keep tabs so the caret lines up with the text
+    let indent: String = loc
+        .text
+        .chars()
+        .take(loc.col - 1)
+        .map(|ch| if ch == '\t' { '\t' } else { ' ' })
+        .collect();
+
+    eprintln!("{}^", indent);
+    exit(1);
+}
+
+fn columns_error(path: &str, expected: usize, found: usize) -> ! {
+    eprintln!("{}: expected {} columns, found {}", path, expected, found);
+    exit(1);
+}
+
+fn read_error(path: &str, err: io::Error) -> ! {
+    eprintln!("failed to read {}: {}", path, err);
+    exit(1);
+}
 
-fn parse_error(at: usize) -> ! {
-    eprintln!("parse error at {}", at);
+fn write_error(path: &str, err: io::Error) -> ! {
+    eprintln!("failed to write {}: {}", path, err);
     exit(1);
 }
 
+/// Writes the output to the file or stdout
+fn write_output<F>(output: Option<&str>, write: F)
+where
+    F: FnOnce(&mut dyn Write) -> io::Result<()>,
+{
+    let res = match output {
+        Some(path) => File::create(path).and_then(|file| {
+            let mut out = BufWriter::new(file);
+            write(&mut out)?;
+            out.flush()
+        }),
+        None => {
+            let mut out = BufWriter::new(io::stdout().lock());
+            write(&mut out).and_then(|_| out.flush())
+        }
+    };
+
+    if let Err(err) = res {
+        write_error(output.unwrap_or("stdout"), err);
+    }
+}
+
+/// Sets options from the arguments to the table
+fn table<'a>(table: Table<'a>, args: &Args) -> Table<'a> {
+    let mut table = table
+        .format(args.format)
+        .style(args.style)
+        .code_fence(args.code_fence);
+
+    // Colors are only for a terminal
+    let color = args.output.is_none()
+        && io::stdout().is_terminal()
+        && env::var_os("NO_COLOR")
+            .filter(|var| !var.is_empty())
+            .is_none();
+
+    if color {
+        table = table.header_style(Ansi::new().bold());
+        for &(col, color) in &args.col_colors {
+            table = table.col_color(col, color);
+        }
+    }
+
+    if let Some(width) = args.max_width {
+        table = table.max_width(width);
+    }
+
+    for &(col, width) in &args.max_col_widths {
+        table = table.max_col_width(col, width);
+    }
+
+    table
+}
+
+/// Applies alignments from the arguments once the header is known
+fn align<'a>(table: Table<'a>, args: &Args) -> Table<'a> {
+    match *args.align.as_slice() {
+        [] => table,
+        [align] => table.align(align),
+        ref aligns => table
+            .aligns(aligns.iter().copied())
+            .unwrap_or_else(|err| args_error(&format!("--align: {}", err))),
+    }
+}
+
+/// Truncates columns wider than `--max-width` or the terminal and warns about them
+fn clamp<'a>(table: Table<'a>, args: &Args) -> Table<'a> {
+    if !args.clamp {
+        return table;
+    }
+
+    let width = args.max_width.unwrap_or_else(|| {
+        env::var("COLUMNS")
+            .ok()
+            .and_then(|columns| columns.parse().ok())
+            .unwrap_or(TERMINAL_WIDTH)
+    });
+
+    for col in table.wide_cols(width) {
+        if args
+            .max_col_widths
+            .iter()
+            .all(|&(limited, _)| limited != col)
+        {
+            eprintln!("warning: column {} is wider than {}, truncated", col, width);
+        }
+    }
+
+    table.max_width(width)
+}
+
+/// Parses the input, exits on a parse error
+fn lexemes<'a>(args: &'a Args, path: &'a str, input: &'a str) -> impl Iterator<Item = Lexeme<'a>> {
+    let parser = match args.input_format {
+        InputFormat::Quoted => Parser::new(input),
+        InputFormat::Csv => Parser::csv(input),
+        InputFormat::Tsv => Parser::tsv(input),
+        InputFormat::Markdown => Parser::markdown(input),
+        InputFormat::Auto => Parser::auto(input),
+    };
+
+    let mut parser = parser.comments(args.comments);
+    iter::from_fn(move || {
+        let lex = match Iterator::next(&mut parser)? {
+            Ok(lex) => lex,
+            Err(err) => parse_error(path, Location::new(input, err.position()), err),
+        };
+
+        if args.lint {
+            lint::warn(path, input, &lex, parser.lexeme_start());
+        }
+
+        Some(lex)
+    })
+}
+
+/// Writes every table of the inputs separated by a blank line
+fn split(args: &Args, inputs: &[(&str, String)]) {
+    if args.headers.is_some() {
+        args_error("--split can't be used with --headers");
+    }
+
+    let tables: Vec<_> = inputs
+        .iter()
+        .flat_map(|(path, input)| Table::split_lexemes(lexemes(args, path, input)))
+        .map(|parsed| clamp(align(table(parsed, args), args), args))
+        .collect();
+
+    write_output(args.output.as_deref(), |out| {
+        for (idx, table) in tables.iter().enumerate() {
+            if idx != 0 {
+                writeln!(out)?;
+            }
+
+            table.write_to(&mut *out)?;
+        }
+
+        Ok(())
+    });
+}
+
 fn main() {
-    // Read all input to string
+    let args = Args::parse(env::args().skip(1)).unwrap_or_else(|msg| args_error(&msg));
+    if args.stream {
+        stream::run(&args, table(Table::new(), &args));
+        return;
+    }
+
+    // Read all inputs to strings
     // since we still need to calculate
     // the table column width
-    let mut input = String::new();
-    io::stdin()
-        .read_to_string(&mut input)
-        .expect("Invalid input");
-
-    let parser = Parser::new(&input).map(|res| match res {
-        Ok(lex) => lex,
-        Err(at) => parse_error(at),
-    });
+    let stdin = ["-".to_owned()];
+    let paths = if args.inputs.is_empty() {
+        &stdin[..]
+    } else {
+        &args.inputs[..]
+    };
+
+    let inputs: Vec<_> = paths
+        .iter()
+        .map(|path| match path.as_str() {
+            "-" => {
+                let mut input = String::new();
+                io::stdin()
+                    .read_to_string(&mut input)
+                    .expect("Invalid input");
+                ("stdin", input)
+            }
+            path => match fs::read_to_string(path) {
+                Ok(input) => (path, input),
+                Err(err) => read_error(path, err),
+            },
+        })
+        .collect();
+
+    // Every data row takes at least one line
+    let lines = inputs
+        .iter()
+        .map(|(_, input)| input.bytes().filter(|&b| b == b'\n').count())
+        .sum();
+
+    if args.split {
+        split(&args, &inputs);
+        return;
+    }
+
+    let mut table = table(Table::new(), &args);
+    let mut comments = vec![];
+    for (idx, (path, input)) in inputs.iter().enumerate() {
+        let lexemes = lexemes(&args, path, input).inspect(|lex| {
+            if let Lexeme::Comment(text) = lex {
+                comments.push(text.clone());
+            }
+        });
+
+        let mut head = Rows::new(lexemes).head();
+        match &args.headers {
+            Some(headers) => {
+                // The input has no header, so its first row is data
+                let row: Vec<_> = head.by_ref().collect();
+                if row.len() != headers.len() {
+                    columns_error(path, headers.len(), row.len());
+                }
+
+                if idx == 0 {
+                    let headers = headers.iter().map(String::as_str);
+                    table = table.head(headers).reserve(lines);
+                }
 
-    let (head, mut tail) = Rows::new(parser).split();
-    let mut table = Table::new().head(head);
+                table = table.tail(row);
+            }
+            None if idx == 0 => table = table.head(head.by_ref()).reserve(lines),
+            None => {
+                let cols_len = head.by_ref().count();
+                if cols_len != table.cols_len() {
+                    columns_error(path, table.cols_len(), cols_len);
+                }
+            }
+        }
 
-    while let Some(row) = tail.row() {
-        table = table.tail(row);
+        let mut tail = head.into_tail();
+        while let Some(row) = tail.row() {
+            table = table.tail(row);
+        }
     }
 
-    print!("{}", table);
+    let table = comments.into_iter().fold(table, Table::comment);
+    let table = clamp(align(table, &args), &args);
+    write_output(args.output.as_deref(), |out| table.write_to(out));
 }
@@ -1,16 +1,30 @@
-use crate::Lexeme;
+mod csv;
+mod fixed;
+mod markdown;
+mod tsv;
+
+use self::{csv::Csv, fixed::Fixed, markdown::Markdown, tsv::Tsv};
+use crate::{Lexeme, ParseError};
+use std::borrow::Cow;
 
 /// The helper struct for tracking a position in the file
 struct Tracker<'a> {
     rest: &'a str,
     pos: usize,
+    /// The position where the last lexeme starts
+    start: usize,
 }
 
 impl<'a> Iterator for Tracker<'a> {
     type Item = char;
 
     fn next(&mut self) -> Option<Self::Item> {
-        let ch = self.rest.chars().next()?;
+        // Decode the char only when the input isn't ASCII
+        let ch = match *self.rest.as_bytes().first()? {
+            byte if byte.is_ascii() => char::from(byte),
+            _ => self.rest.chars().next()?,
+        };
+
         let len = ch.len_utf8();
         self.rest = &self.rest[len..];
         self.pos += len;
@@ -18,53 +32,498 @@ impl<'a> Iterator for Tracker<'a> {
     }
 }
 
+impl<'a> Tracker<'a> {
+    /// Skips `len` bytes and returns them
+    fn take(&mut self, len: usize) -> &'a str {
+        let (taken, rest) = self.rest.split_at(len);
+        self.rest = rest;
+        self.pos += len;
+        taken
+    }
+
+    /// Reads the rest of the line and whether it ends with a line break
+    fn line(&mut self) -> Option<(&'a str, bool)> {
+        if self.rest.is_empty() {
+            return None;
+        }
+
+        let rest = self.rest;
+        Some(match self.skip_to(b'\n') {
+            Some(len) => (&rest[..len], true),
+            None => (self.take(rest.len()), false),
+        })
+    }
+
+    /// Skips the following line if it has only dashes, colons, pipes and whitespace
+    fn skip_alignment(&mut self) {
+        let len = self.rest.find('\n').map_or(self.rest.len(), |len| len + 1);
+        let line = &self.rest[..len];
+        let alignment = line.contains('-')
+            && line
+                .chars()
+                .all(|ch| matches!(ch, '-' | ':' | '|') || ch.is_whitespace());
+
+        if alignment {
+            self.take(len);
+        }
+    }
+
+    /// Skips following lines that contain only whitespace.
+    /// Returns true if at least one line is skipped.
+    fn skip_blank_lines(&mut self) -> bool {
+        let mut skipped = false;
+        while let Some(len) = self.rest.find('\n') {
+            if !self.rest[..len].chars().all(char::is_whitespace) {
+                break;
+            }
+
+            self.rest = &self.rest[len + 1..];
+            self.pos += len + 1;
+            skipped = true;
+        }
+        skipped
+    }
+
+    /// Skips the input up to and including the ASCII `byte`.
+    /// Returns the number of bytes before it.
+    fn skip_to(&mut self, byte: u8) -> Option<usize> {
+        debug_assert!(byte.is_ascii());
+
+        // An ASCII byte never occurs inside a multibyte char,
+        // so it's safe to search over bytes
+        let len = self.rest.bytes().position(|b| b == byte)?;
+        self.rest = &self.rest[len + 1..];
+        self.pos += len + 1;
+        Some(len)
+    }
+
+    /// Skips past the char and returns the number of bytes before it
+    fn skip_past(&mut self, ch: char) -> Option<usize> {
+        let len = self.rest.find(ch)?;
+        self.take(len + ch.len_utf8());
+        Some(len)
+    }
+}
+
+/// Returns the cell between the byte positions,
+/// fails instead of panicking if they aren't on char boundaries
+fn slice(input: &str, start: usize, end: usize) -> Result<&str, ParseError> {
+    input.get(start..end).ok_or(ParseError::BadSlice(start))
+}
+
+/// Counts tabs and commas of the line outside of quotes
+fn count_delimiters(line: &str) -> (usize, usize) {
+    let mut quoted = false;
+    let (mut tabs, mut commas) = (0, 0);
+    for ch in line.chars() {
+        match ch {
+            '"' => quoted = !quoted,
+            '\t' if !quoted => tabs += 1,
+            ',' if !quoted => commas += 1,
+            _ => (),
+        }
+    }
+    (tabs, commas)
+}
+
+/// Whether only whitespace precedes the position on its line
+fn line_start(input: &str, at: usize) -> bool {
+    let before = &input[..at];
+    let start = before.rfind('\n').map_or(0, |idx| idx + 1);
+    before[start..].trim().is_empty()
+}
+
+/// Returns the end of the line at the position if the line has only the marker
+fn marker_at(input: &str, at: usize, marker: Option<&str>) -> Option<usize> {
+    let marker = marker?;
+    if !line_start(input, at) {
+        return None;
+    }
+
+    let rest = &input[at..];
+    let len = rest.find('\n').map_or(rest.len(), |idx| idx + 1);
+    Some(at + len).filter(|_| rest[..len].trim() == marker)
+}
+
+/// Whether the comment prefix is at the position and only whitespace precedes it
+fn comment_at(input: &str, at: usize, prefix: Option<&str>) -> bool {
+    match prefix {
+        Some(prefix) => input[at..].starts_with(prefix) && line_start(input, at),
+        None => false,
+    }
+}
+
+/// The line and column of a position in the input
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct Location<'a> {
+    /// The 1-based line number
+    pub line: usize,
+    /// The 1-based column counted in chars
+    pub col: usize,
+    /// The text of the line without the line break
+    pub text: &'a str,
+}
+
+impl<'a> Location<'a> {
+    /// Resolves the byte position in the input
+    pub fn new(input: &'a str, at: usize) -> Self {
+        let before = &input[..at];
+        let start = before.rfind('\n').map_or(0, |idx| idx + 1);
+        let end = input[at..].find('\n').map_or(input.len(), |idx| at + idx);
+
+        Self {
+            line: before.bytes().filter(|&b| b == b'\n').count() + 1,
+            col: input[start..at].chars().count() + 1,
+            text: input[start..end].trim_end_matches('\r'),
+        }
+    }
+}
+
+/// The end of a row
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Terminator {
+    /// The char ends the row, line breaks become whitespace
+    Char(char),
+    /// A line with only whitespace ends the row,
+    /// a single line break is whitespace
+    BlankLine,
+}
+
+impl Default for Terminator {
+    fn default() -> Self {
+        Self::Char('\n')
+    }
+}
+
+/// What comes next when cells are separated by a char
+#[derive(Copy, Clone, Eq, PartialEq)]
+enum Expect {
+    /// A cell at the row start
+    Cell,
+    /// A separator after a cell
+    Separator,
+    /// A cell after the separator at the given position
+    SeparatedCell(usize),
+}
+
+/// The syntax of the input
+enum Mode<'a> {
+    /// Cells are quoted
+    Quoted,
+    /// A Markdown pipe table
+    Markdown(Markdown<'a>),
+    /// Comma-separated values
+    Csv(Csv),
+    /// Tab-separated values
+    Tsv(Tsv<'a>),
+    /// Columns of fixed width
+    Fixed(Fixed<'a>),
+}
+
 pub struct Parser<'a> {
     input: &'a str,
+    mode: Mode<'a>,
     tracker: Tracker<'a>,
     lex_start: usize,
     running: bool,
+    separator: Option<char>,
+    terminator: Terminator,
+    expect: Expect,
+    skip_alignment: bool,
+    /// The prefix of comment lines
+    comment: Option<&'a str>,
+    strip_cr: bool,
+    /// The chars around cells of the default syntax
+    open: char,
+    close: char,
+    /// Quoted cells keep their quotes
+    raw: bool,
+    /// The line that ends the table
+    end_marker: Option<&'a str>,
+    /// The next lexeme starts a row
+    row_start: bool,
+    /// The header row isn't ended yet
+    header: bool,
 }
 
 impl<'a> Parser<'a> {
     pub fn new(input: &'a str) -> Self {
         Self {
             input,
+            mode: Mode::Quoted,
             tracker: Tracker {
                 rest: input,
                 pos: 0,
+                start: 0,
             },
             lex_start: 0,
             running: true,
+            separator: None,
+            terminator: Terminator::default(),
+            expect: Expect::Cell,
+            skip_alignment: false,
+            comment: None,
+            strip_cr: false,
+            open: '"',
+            close: '"',
+            raw: false,
+            end_marker: None,
+            row_start: true,
+            header: true,
+        }
+    }
+
+    /// Parses a Markdown pipe table. Cells are trimmed,
+    /// the alignment row is skipped and `\|` is unescaped.
+    pub fn markdown(input: &'a str) -> Self {
+        Self {
+            mode: Mode::Markdown(Markdown::default()),
+            ..Self::new(input)
+        }
+    }
+
+    /// Parses CSV, cells may be quoted with `""` as an escaped quote.
+    /// Errors point at an unterminated quote or a char after a quoted cell.
+    pub fn csv(input: &'a str) -> Self {
+        Self {
+            mode: Mode::Csv(Csv::default()),
+            ..Self::new(input)
+        }
+    }
+
+    /// Parses tab-separated values without quoting
+    pub fn tsv(input: &'a str) -> Self {
+        Self {
+            mode: Mode::Tsv(Tsv::default()),
+            ..Self::new(input)
+        }
+    }
+
+    /// Detects the syntax from the first line: quoted cells, CSV if commas
+    /// separate cells or TSV if tabs do. Ambiguous input is read as quoted cells.
+    pub fn auto(input: &'a str) -> Self {
+        let line = input
+            .lines()
+            .find(|line| !line.trim().is_empty())
+            .unwrap_or_default();
+
+        if Parser::new(line).all(|lex| lex.is_ok()) {
+            return Self::new(input);
+        }
+
+        match count_delimiters(line) {
+            (tabs, commas) if tabs > 0 && tabs >= commas => Self::tsv(input),
+            (_, commas) if commas > 0 => Self::csv(input),
+            _ => Self::new(input),
+        }
+    }
+
+    /// Parses columns of fixed width like `ls -l` output. Columns start
+    /// at the given char positions of each line, cells are trimmed.
+    pub fn fixed_width(input: &'a str, starts: &[usize]) -> Self {
+        Self {
+            mode: Mode::Fixed(Fixed::new(starts.to_vec())),
+            ..Self::new(input)
+        }
+    }
+
+    /// Parses columns of fixed width, a column starts
+    /// after a position where every line has whitespace
+    pub fn fixed_width_auto(input: &'a str) -> Self {
+        Self {
+            mode: Mode::Fixed(Fixed::detect(input)),
+            ..Self::new(input)
+        }
+    }
+
+    /// Requires the separator char between cells of a row,
+    /// by default cells are separated by whitespace
+    pub fn separator(mut self, separator: char) -> Self {
+        self.separator = Some(separator);
+        self
+    }
+
+    /// Sets what ends a row, by default it's a line break
+    pub fn terminator(mut self, terminator: Terminator) -> Self {
+        self.terminator = terminator;
+        self
+    }
+
+    /// Skips a line like `---|:--:` right after the header,
+    /// left from a Markdown table
+    pub fn skip_alignment(mut self, enable: bool) -> Self {
+        self.skip_alignment = enable;
+        self
+    }
+
+    /// Reads a `#` in place of the first cell of a row
+    /// as a comment up to the line end. Only quoted input has comments,
+    /// other syntaxes read the `#` as a cell.
+    pub fn comments(mut self, enable: bool) -> Self {
+        self.comment = Some("#").filter(|_| enable);
+        self
+    }
+
+    /// Sets the prefix of comments like `//` or `;` and enables them.
+    /// Like [`comments`](Parser::comments), it only applies to quoted input.
+    pub fn comment_prefix(mut self, prefix: &'a str) -> Self {
+        self.comment = Some(prefix).filter(|prefix| !prefix.is_empty());
+        self
+    }
+
+    /// Removes carriage returns from cells, so a `\r` left from mixed
+    /// line endings neither moves the terminal cursor nor counts in the width
+    pub fn strip_cr(mut self, enable: bool) -> Self {
+        self.strip_cr = enable;
+        self
+    }
+
+    /// Quotes cells of the default syntax with the pair of chars like `«` and `»`
+    /// instead of `"`. The first `close` char ends the cell.
+    pub fn with_brackets(mut self, open: char, close: char) -> Self {
+        self.open = open;
+        self.close = close;
+        self
+    }
+
+    /// Yields quoted cells with their quotes, so the source can be rebuilt.
+    /// Only cells of the default syntax are quoted.
+    pub fn raw_cells(mut self, enable: bool) -> Self {
+        self.raw = enable;
+        self
+    }
+
+    /// Stops at a line with only the marker like `---END---`, the input
+    /// after the marker line starts at [`position`](Parser::position)
+    pub fn end_marker(mut self, marker: &'a str) -> Self {
+        self.end_marker = Some(marker);
+        self
+    }
+
+    /// Returns the byte offset of the input after the last parsed lexeme
+    pub fn position(&self) -> usize {
+        self.tracker.pos
+    }
+
+    /// Returns the byte offset of the input where the last parsed lexeme starts,
+    /// a quoted cell starts at its opening quote
+    pub fn lexeme_start(&self) -> usize {
+        self.tracker.start
+    }
+
+    fn next(&mut self) -> Option<Result<Lexeme<'a>, ParseError>> {
+        if self.row_start {
+            let tracker = &mut self.tracker;
+            let pos = tracker.pos;
+            if let Some(end) = marker_at(self.input, pos, self.end_marker) {
+                tracker.take(end - pos);
+                self.running = false;
+                return None;
+            }
+        }
+
+        let lex = self.next_lexeme()?;
+        // A comment takes the whole line
+        self.row_start = matches!(lex, Ok(Lexeme::NewLine) | Ok(Lexeme::Comment(_)));
+        if !self.strip_cr {
+            return Some(lex);
         }
+
+        Some(lex.map(|lex| match lex {
+            Lexeme::Cell(cell) if cell.contains('\r') => {
+                Lexeme::Cell(Cow::Owned(cell.replace('\r', "")))
+            }
+            lex => lex,
+        }))
     }
 
-    fn next(&mut self) -> Option<Result<Lexeme<'a>, usize>> {
-        const QUOTE: char = '"';
+    fn next_lexeme(&mut self) -> Option<Result<Lexeme<'a>, ParseError>> {
+        match &mut self.mode {
+            Mode::Quoted => {
+                let lex = self.next_quoted();
+                self.tracker.start = self.lex_start;
+                lex
+            }
+            Mode::Markdown(markdown) => markdown.next(&mut self.tracker).map(Ok),
+            Mode::Csv(csv) => csv.next(&mut self.tracker),
+            Mode::Tsv(tsv) => tsv.next(&mut self.tracker).map(Ok),
+            Mode::Fixed(fixed) => fixed.next(&mut self.tracker).map(Ok),
+        }
+    }
 
+    fn next_quoted(&mut self) -> Option<Result<Lexeme<'a>, ParseError>> {
         let tracker = &mut self.tracker;
 
         loop {
             self.lex_start = tracker.pos;
-            match tracker.next()? {
-                '\n' => return Some(Ok(Lexeme::NewLine)),
-                ch if ch.is_whitespace() => continue,
-                QUOTE => break,
-                _ => return Some(Err(self.lex_start)),
+            let ch = match (tracker.next(), self.expect) {
+                (None, Expect::SeparatedCell(at)) => return Some(Err(ParseError::MissingCell(at))),
+                (None, _) => return None,
+                (Some(ch), _) => ch,
+            };
+
+            let terminator = match self.terminator {
+                Terminator::Char(terminator) => ch == terminator,
+                Terminator::BlankLine => ch == '\n' && tracker.skip_blank_lines(),
+            };
+
+            match (ch, self.expect) {
+                // The separator must be followed by a cell
+                (_, Expect::SeparatedCell(at)) if terminator => {
+                    return Some(Err(ParseError::MissingCell(at)))
+                }
+                _ if terminator => {
+                    if self.header && self.skip_alignment {
+                        tracker.skip_alignment();
+                    }
+
+                    self.header = false;
+                    self.expect = Expect::Cell;
+                    return Some(Ok(Lexeme::NewLine));
+                }
+                (ch, Expect::Separator) if Some(ch) == self.separator => {
+                    self.expect = Expect::SeparatedCell(self.lex_start);
+                }
+                (ch, _) if ch.is_whitespace() => continue,
+                (ch, Expect::Cell) if comment_at(self.input, self.lex_start, self.comment) => {
+                    // The first char of the prefix is already read
+                    let prefix = self.comment.map_or(0, str::len);
+                    tracker.take(prefix - ch.len_utf8());
+                    let (line, _) = tracker.line().unwrap_or_default();
+                    let text = line.strip_suffix('\r').unwrap_or(line);
+                    return Some(Ok(Lexeme::Comment(Cow::Borrowed(text))));
+                }
+                (ch, Expect::Cell) | (ch, Expect::SeparatedCell(_)) if ch == self.open => break,
+                _ => return Some(Err(ParseError::UnexpectedChar(self.lex_start))),
             }
         }
 
-        Some(match tracker.position(|ch| ch == QUOTE) {
-            None => Err(self.lex_start),
+        if self.separator.is_some() {
+            self.expect = Expect::Separator;
+        }
+
+        Some(match tracker.skip_past(self.close) {
+            None => Err(ParseError::UnmatchedQuote(self.lex_start)),
             Some(len) => {
-                let start = self.lex_start + QUOTE.len_utf8();
-                Ok(Lexeme::Cell(&self.input[start..start + len]))
+                let start = self.lex_start + self.open.len_utf8();
+                let cell = if self.raw {
+                    slice(
+                        self.input,
+                        self.lex_start,
+                        start + len + self.close.len_utf8(),
+                    )
+                } else {
+                    slice(self.input, start, start + len)
+                };
+
+                cell.map(|cell| Lexeme::Cell(Cow::Borrowed(cell)))
             }
         })
     }
 }
 
 impl<'a> Iterator for Parser<'a> {
-    type Item = Result<Lexeme<'a>, usize>;
+    type Item = Result<Lexeme<'a>, ParseError>;
 
     fn next(&mut self) -> Option<Self::Item> {
         if self.running {
@@ -91,21 +550,34 @@ mod tests {
     fn parse_empty_cell() {
         let parser = Parser::new(r#" """" "#);
         let parsed: Vec<_> = parser.map(Result::unwrap).collect();
-        assert_eq!(parsed, [Lexeme::Cell(""), Lexeme::Cell("")]);
+        assert_eq!(parsed, [Lexeme::Cell("".into()), Lexeme::Cell("".into())]);
     }
 
     #[test]
     fn parse_one() {
         let parser = Parser::new(r#" "hi" "#);
         let parsed: Vec<_> = parser.map(Result::unwrap).collect();
-        assert_eq!(parsed, [Lexeme::Cell("hi")]);
+        assert_eq!(parsed, [Lexeme::Cell("hi".into())]);
     }
 
     #[test]
     fn parse_two() {
         let parser = Parser::new(r#" "hi" "fi" "#);
         let parsed: Vec<_> = parser.map(Result::unwrap).collect();
-        assert_eq!(parsed, [Lexeme::Cell("hi"), Lexeme::Cell("fi")]);
+        assert_eq!(
+            parsed,
+            [Lexeme::Cell("hi".into()), Lexeme::Cell("fi".into())]
+        );
+    }
+
+    #[test]
+    fn parse_multibyte() {
+        let parser = Parser::new(r#" "héllo" "мир" "#);
+        let parsed: Vec<_> = parser.map(Result::unwrap).collect();
+        assert_eq!(
+            parsed,
+            [Lexeme::Cell("héllo".into()), Lexeme::Cell("мир".into())]
+        );
     }
 
     #[test]
@@ -127,25 +599,365 @@ mod tests {
         assert_eq!(
             parsed,
             [
-                Lexeme::Cell("hi"),
+                Lexeme::Cell("hi".into()),
+                Lexeme::NewLine,
+                Lexeme::Cell("fi".into()),
+                Lexeme::NewLine,
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_comments() {
+        let parser = Parser::new("# note\r\n\"a\"\n  #x\n\"b\"").comments(true);
+        let parsed: Vec<_> = parser.map(Result::unwrap).collect();
+        assert_eq!(
+            parsed,
+            [
+                Lexeme::Comment(" note".into()),
+                Lexeme::Cell("a".into()),
+                Lexeme::NewLine,
+                Lexeme::Comment("x".into()),
+                Lexeme::Cell("b".into()),
+            ]
+        );
+
+        let parser = Parser::new("\"a\" #").comments(true);
+        let parsed: Vec<_> = parser.collect();
+        assert_eq!(
+            parsed,
+            [
+                Ok(Lexeme::Cell("a".into())),
+                Err(ParseError::UnexpectedChar(4))
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_strip_cr() {
+        let input = "\"a\rb\" \"c\r\nd\"\r\n";
+        let parser = Parser::new(input).strip_cr(true);
+        let parsed: Vec<_> = parser.map(Result::unwrap).collect();
+        assert_eq!(
+            parsed,
+            [
+                Lexeme::Cell("ab".into()),
+                Lexeme::Cell("c\nd".into()),
+                Lexeme::NewLine,
+            ]
+        );
+
+        let parser = Parser::new(input);
+        let parsed: Vec<_> = parser.map(Result::unwrap).collect();
+        assert_eq!(parsed[0], Lexeme::Cell("a\rb".into()));
+    }
+
+    #[test]
+    fn parse_raw_cells() {
+        let input = "\"a b\"  \"\"\n";
+        let parsed: Vec<_> = Parser::new(input).map(Result::unwrap).collect();
+        assert_eq!(
+            parsed,
+            [
+                Lexeme::Cell("a b".into()),
+                Lexeme::Cell("".into()),
+                Lexeme::NewLine,
+            ]
+        );
+
+        let parser = Parser::new(input).raw_cells(true);
+        let parsed: Vec<_> = parser.map(Result::unwrap).collect();
+        assert_eq!(
+            parsed,
+            [
+                Lexeme::Cell("\"a b\"".into()),
+                Lexeme::Cell("\"\"".into()),
+                Lexeme::NewLine,
+            ]
+        );
+    }
+
+    #[test]
+    fn position() {
+        let input = "\"a\" \"bc\"\n\"d\"";
+        let mut parser = Parser::new(input);
+        assert_eq!(parser.position(), 0);
+        assert_eq!(parser.next(), Some(Ok(Lexeme::Cell("a".into()))));
+        assert_eq!(parser.position(), 3);
+        assert_eq!(parser.next(), Some(Ok(Lexeme::Cell("bc".into()))));
+        assert_eq!(parser.position(), 8);
+        assert_eq!(parser.next(), Some(Ok(Lexeme::NewLine)));
+        assert_eq!(parser.position(), 9);
+        assert_eq!(&input[parser.position()..], "\"d\"");
+    }
+
+    #[test]
+    fn lexeme_start() {
+        let starts = |mut parser: Parser| {
+            let mut starts = vec![];
+            while let Some(Ok(_)) = parser.next() {
+                starts.push(parser.lexeme_start());
+            }
+            starts
+        };
+
+        assert_eq!(starts(Parser::new(" \"a\" \"b\"\n\"c\"")), [1, 5, 8, 9]);
+        assert_eq!(starts(Parser::csv("a,\"b\"\nc")), [0, 2, 5, 6]);
+        assert_eq!(starts(Parser::tsv("a\tb\r\nc")), [0, 2, 3, 5]);
+        assert_eq!(
+            starts(Parser::markdown("| a |  b |\n|---|---|\n|c|")),
+            [2, 7, 10, 22]
+        );
+        assert_eq!(
+            starts(Parser::fixed_width("a  b\n c d", &[0, 3])),
+            [0, 3, 4, 6, 8]
+        );
+    }
+
+    #[test]
+    fn parse_end_marker() {
+        let input = "\"a\" \"b\"\n\"1\" \"2\"\n ---END---\n\"c\"\n";
+        let mut parser = Parser::new(input).end_marker("---END---");
+        let parsed: Vec<_> = parser.by_ref().map(Result::unwrap).collect();
+        assert_eq!(
+            parsed,
+            [
+                Lexeme::Cell("a".into()),
+                Lexeme::Cell("b".into()),
+                Lexeme::NewLine,
+                Lexeme::Cell("1".into()),
+                Lexeme::Cell("2".into()),
+                Lexeme::NewLine,
+            ]
+        );
+
+        assert_eq!(parser.by_ref().count(), 0);
+        assert_eq!(&input[parser.position()..], "\"c\"\n");
+
+        let input = "\"a\"\n# note\n---END---\n\"c\"\n";
+        let parser = Parser::new(input).comments(true).end_marker("---END---");
+        let parsed: Vec<_> = parser.map(Result::unwrap).collect();
+        assert_eq!(
+            parsed,
+            [
+                Lexeme::Cell("a".into()),
+                Lexeme::NewLine,
+                Lexeme::Comment(" note".into()),
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_brackets() {
+        let input = "«a» «b \"c\"»\n«»";
+        let parser = Parser::new(input).with_brackets('«', '»');
+        let parsed: Vec<_> = parser.map(Result::unwrap).collect();
+        assert_eq!(
+            parsed,
+            [
+                Lexeme::Cell("a".into()),
+                Lexeme::Cell("b \"c\"".into()),
+                Lexeme::NewLine,
+                Lexeme::Cell("".into()),
+            ]
+        );
+
+        let parser = Parser::new("«a» «b").with_brackets('«', '»');
+        let parsed: Vec<_> = parser.collect();
+        assert_eq!(parsed.last(), Some(&Err(ParseError::UnmatchedQuote(6))));
+    }
+
+    #[test]
+    fn parse_auto() {
+        let parse = |input| -> Vec<_> { Parser::auto(input).map(Result::unwrap).collect() };
+        let expected = [
+            Lexeme::Cell("name".into()),
+            Lexeme::Cell("note".into()),
+            Lexeme::NewLine,
+            Lexeme::Cell("kiwi".into()),
+            Lexeme::Cell("a, b".into()),
+            Lexeme::NewLine,
+        ];
+
+        assert_eq!(parse("name,note\nkiwi,\"a, b\"\n"), expected);
+        assert_eq!(parse("name\tnote\nkiwi\ta, b\n"), expected);
+        assert_eq!(parse("\"name\"\t\"note\"\n\"kiwi\" \"a, b\"\n"), expected);
+        assert_eq!(
+            Parser::auto("name note").next(),
+            Some(Err(ParseError::UnexpectedChar(0)))
+        );
+    }
+
+    #[test]
+    fn slice_bounds() {
+        let input = "\"añb\"";
+        assert_eq!(slice(input, 1, 5), Ok("añb"));
+        assert_eq!(slice(input, 3, 5), Err(ParseError::BadSlice(3)));
+        assert_eq!(slice(input, 1, 7), Err(ParseError::BadSlice(1)));
+    }
+
+    #[test]
+    fn parse_comment_prefix() {
+        let input = "// note\n\"a\" \"//b\"\n /\n";
+        let parser = Parser::new(input).comment_prefix("//");
+        let parsed: Vec<_> = parser.collect();
+        assert_eq!(
+            parsed,
+            [
+                Ok(Lexeme::Comment(" note".into())),
+                Ok(Lexeme::Cell("a".into())),
+                Ok(Lexeme::Cell("//b".into())),
+                Ok(Lexeme::NewLine),
+                Err(ParseError::UnexpectedChar(19)),
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_separator() {
+        let parser = Parser::new(" \"a\", \"b\" ,\"\"\n\"c\"").separator(',');
+        let parsed: Vec<_> = parser.map(Result::unwrap).collect();
+        assert_eq!(
+            parsed,
+            [
+                Lexeme::Cell("a".into()),
+                Lexeme::Cell("b".into()),
+                Lexeme::Cell("".into()),
+                Lexeme::NewLine,
+                Lexeme::Cell("c".into()),
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_separator_error() {
+        let parser = Parser::new(r#""a" "b""#).separator(',');
+        let parsed: Vec<_> = parser.collect();
+        assert_eq!(
+            parsed,
+            [
+                Ok(Lexeme::Cell("a".into())),
+                Err(ParseError::UnexpectedChar(4))
+            ]
+        );
+
+        let parser = Parser::new(r#""a","#).separator(',');
+        let parsed: Vec<_> = parser.collect();
+        assert_eq!(
+            parsed,
+            [
+                Ok(Lexeme::Cell("a".into())),
+                Err(ParseError::MissingCell(3))
+            ]
+        );
+
+        let parser = Parser::new(r#","a""#).separator(',');
+        let parsed: Vec<_> = parser.collect();
+        assert_eq!(parsed, [Err(ParseError::UnexpectedChar(0))]);
+    }
+
+    #[test]
+    fn parse_terminator() {
+        let parser = Parser::new("\"a\"\n\"b\"; \"c\"").terminator(Terminator::Char(';'));
+        let parsed: Vec<_> = parser.map(Result::unwrap).collect();
+        assert_eq!(
+            parsed,
+            [
+                Lexeme::Cell("a".into()),
+                Lexeme::Cell("b".into()),
                 Lexeme::NewLine,
-                Lexeme::Cell("fi"),
+                Lexeme::Cell("c".into()),
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_blank_line_terminator() {
+        let parser = Parser::new("\"a\" \"b\"\n\"c\"\n \t\r\n\n\"1\"\n\"2\"\n")
+            .terminator(Terminator::BlankLine);
+
+        let parsed: Vec<_> = parser.map(Result::unwrap).collect();
+        assert_eq!(
+            parsed,
+            [
+                Lexeme::Cell("a".into()),
+                Lexeme::Cell("b".into()),
+                Lexeme::Cell("c".into()),
                 Lexeme::NewLine,
+                Lexeme::Cell("1".into()),
+                Lexeme::Cell("2".into()),
             ]
         );
     }
 
+    #[test]
+    fn location() {
+        let input = "\"a\"\r\n \"é\" x\n";
+        let at = input.find('x').unwrap();
+        assert_eq!(
+            Location::new(input, at),
+            Location {
+                line: 2,
+                col: 6,
+                text: " \"é\" x",
+            }
+        );
+
+        assert_eq!(Location::new(input, 0).text, "\"a\"");
+    }
+
     #[test]
     fn parse_error_start() {
         let parser = Parser::new("...");
         let parsed: Vec<_> = parser.collect();
-        assert_eq!(parsed, [Err(0)]);
+        assert_eq!(parsed, [Err(ParseError::UnexpectedChar(0))]);
     }
 
     #[test]
     fn parse_error_end() {
         let parser = Parser::new("\"...");
         let parsed: Vec<_> = parser.collect();
-        assert_eq!(parsed, [Err(0)]);
+        assert_eq!(parsed, [Err(ParseError::UnmatchedQuote(0))]);
+    }
+
+    #[test]
+    fn parse_skip_alignment() {
+        let parser = Parser::new("\"a\" \"b\"\n|---|:-:|\n\"-\" \"c\"\n---\n").skip_alignment(true);
+        let parsed: Vec<_> = parser.collect();
+        assert_eq!(
+            parsed,
+            [
+                Ok(Lexeme::Cell("a".into())),
+                Ok(Lexeme::Cell("b".into())),
+                Ok(Lexeme::NewLine),
+                Ok(Lexeme::Cell("-".into())),
+                Ok(Lexeme::Cell("c".into())),
+                Ok(Lexeme::NewLine),
+                Err(ParseError::UnexpectedChar(26)),
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_error_quote() {
+        let parser = Parser::new("\"a\" \"");
+        let parsed: Vec<_> = parser.collect();
+        assert_eq!(
+            parsed,
+            [
+                Ok(Lexeme::Cell("a".into())),
+                Err(ParseError::UnmatchedQuote(4)),
+            ]
+        );
+
+        let parser = Parser::csv("a,b\"");
+        let parsed: Vec<_> = parser.collect();
+        assert_eq!(
+            parsed,
+            [
+                Ok(Lexeme::Cell("a".into())),
+                Err(ParseError::UnmatchedQuote(3)),
+            ]
+        );
     }
 }
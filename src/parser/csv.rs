@@ -0,0 +1,145 @@
+use super::Tracker;
+use crate::{Lexeme, ParseError};
+use std::borrow::Cow;
+
+/// Returns the length of the line break at the start of the text
+fn line_break(text: &str) -> Option<usize> {
+    if text.starts_with('\n') {
+        Some(1)
+    } else if text.starts_with("\r\n") {
+        Some(2)
+    } else {
+        None
+    }
+}
+
+/// The state of parsing RFC 4180 CSV
+#[derive(Default)]
+pub(super) struct Csv {
+    /// A cell follows the comma
+    after_comma: bool,
+}
+
+impl Csv {
+    pub(super) fn next<'a>(
+        &mut self,
+        tracker: &mut Tracker<'a>,
+    ) -> Option<Result<Lexeme<'a>, ParseError>> {
+        let rest = tracker.rest;
+        tracker.start = tracker.pos;
+        let end = rest.is_empty() || line_break(rest).is_some();
+        if end && self.after_comma {
+            // The row ends with an empty cell
+            self.after_comma = false;
+            return Some(Ok(Lexeme::Cell(Cow::Borrowed(""))));
+        }
+
+        if let Some(len) = line_break(rest) {
+            tracker.take(len);
+            return Some(Ok(Lexeme::NewLine));
+        }
+
+        if rest.is_empty() {
+            return None;
+        }
+
+        let cell = if rest.starts_with('"') {
+            let start = tracker.pos;
+            match Self::quoted(tracker) {
+                Some(cell) => cell,
+                None => return Some(Err(ParseError::UnmatchedQuote(start))),
+            }
+        } else {
+            let len = rest.find(&[',', '\n'][..]).unwrap_or(rest.len());
+            if let Some(quote) = rest[..len].find('"') {
+                // Only a whole cell may be quoted
+                return Some(Err(ParseError::UnmatchedQuote(tracker.pos + quote)));
+            }
+
+            let cell = tracker.take(len);
+            let cell = if tracker.rest.starts_with('\n') {
+                cell.strip_suffix('\r').unwrap_or(cell)
+            } else {
+                cell
+            };
+            Cow::Borrowed(cell)
+        };
+
+        let rest = tracker.rest;
+        if rest.starts_with(',') {
+            tracker.take(1);
+            self.after_comma = true;
+        } else if rest.is_empty() || line_break(rest).is_some() {
+            self.after_comma = false;
+        } else {
+            // Only a separator may follow the quoted cell
+            return Some(Err(ParseError::UnexpectedChar(tracker.pos)));
+        }
+
+        Some(Ok(Lexeme::Cell(cell)))
+    }
+
+    /// Reads the quoted cell, returns `None` if it's not terminated
+    fn quoted<'a>(tracker: &mut Tracker<'a>) -> Option<Cow<'a, str>> {
+        const QUOTE: u8 = b'"';
+
+        let rest = &tracker.rest[1..];
+        let mut len = 0;
+        let mut escaped = false;
+        loop {
+            len += rest[len..].bytes().position(|b| b == QUOTE)?;
+            if rest.as_bytes().get(len + 1) == Some(&QUOTE) {
+                escaped = true;
+                len += 2;
+            } else {
+                break;
+            }
+        }
+
+        tracker.take(len + 2);
+        let cell = &rest[..len];
+        Some(if escaped {
+            Cow::Owned(cell.replace("\"\"", "\""))
+        } else {
+            Cow::Borrowed(cell)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Lexeme, ParseError, Parser};
+
+    #[test]
+    fn parse_csv() {
+        let parser = Parser::csv("a,\"b,\"\"c\"\"\"\r\n,\"multi\nline\",\n1");
+        let parsed: Vec<_> = parser.map(Result::unwrap).collect();
+        assert_eq!(
+            parsed,
+            [
+                Lexeme::Cell("a".into()),
+                Lexeme::Cell("b,\"c\"".into()),
+                Lexeme::NewLine,
+                Lexeme::Cell("".into()),
+                Lexeme::Cell("multi\nline".into()),
+                Lexeme::Cell("".into()),
+                Lexeme::NewLine,
+                Lexeme::Cell("1".into()),
+            ]
+        );
+
+        let parser = Parser::csv("a,\"b\nc");
+        let parsed: Vec<_> = parser.collect();
+        assert_eq!(
+            parsed,
+            [
+                Ok(Lexeme::Cell("a".into())),
+                Err(ParseError::UnmatchedQuote(2))
+            ]
+        );
+
+        let parser = Parser::csv("\"a\"b");
+        let parsed: Vec<_> = parser.collect();
+        assert_eq!(parsed, [Err(ParseError::UnexpectedChar(3))]);
+    }
+}
@@ -0,0 +1,128 @@
+use super::Tracker;
+use crate::Lexeme;
+use std::borrow::Cow;
+
+/// Finds starts of columns in chars at positions
+/// where every line has whitespace before and a non-whitespace char after
+fn detect_columns(input: &str) -> Vec<usize> {
+    let mut blank: Vec<bool> = vec![];
+    for line in input.lines().filter(|line| !line.trim().is_empty()) {
+        for (idx, ch) in line.chars().enumerate() {
+            if idx == blank.len() {
+                blank.push(true);
+            }
+
+            blank[idx] &= ch.is_whitespace();
+        }
+    }
+
+    let first = blank.iter().position(|&blank| !blank).unwrap_or(0);
+    let starts = (first + 1..blank.len()).filter(|&idx| blank[idx - 1] && !blank[idx]);
+    Some(first).into_iter().chain(starts).collect()
+}
+
+/// Returns the part of the line between char positions `from` and `to`
+/// and the byte position where it starts
+fn slice_chars(line: &str, from: usize, to: Option<usize>) -> (usize, &str) {
+    let byte = |pos| {
+        line.char_indices()
+            .nth(pos)
+            .map_or(line.len(), |(idx, _)| idx)
+    };
+    let start = byte(from);
+    let end = to.map_or(line.len(), byte);
+    (start, &line[start..end.max(start)])
+}
+
+/// The state of parsing columns of fixed width
+pub(super) struct Fixed<'a> {
+    /// Starts of columns in chars
+    starts: Vec<usize>,
+    /// The current line
+    row: Option<&'a str>,
+    /// The position where the current line starts
+    row_start: usize,
+    /// The next column of the line
+    col: usize,
+    /// The current row ends with a line break
+    new_line: bool,
+}
+
+impl<'a> Fixed<'a> {
+    pub(super) fn new(starts: Vec<usize>) -> Self {
+        Self {
+            starts,
+            row: None,
+            row_start: 0,
+            col: 0,
+            new_line: false,
+        }
+    }
+
+    pub(super) fn detect(input: &str) -> Self {
+        Self::new(detect_columns(input))
+    }
+
+    pub(super) fn next(&mut self, tracker: &mut Tracker<'a>) -> Option<Lexeme<'a>> {
+        loop {
+            if let Some(row) = self.row {
+                let from = self.starts[self.col];
+                let to = self.starts.get(self.col + 1).copied();
+                self.col += 1;
+                if self.col == self.starts.len() {
+                    self.row = None;
+                }
+
+                let (start, cell) = slice_chars(row, from, to);
+                let trimmed = cell.trim_start();
+                tracker.start = self.row_start + start + cell.len() - trimmed.len();
+                return Some(Lexeme::Cell(Cow::Borrowed(trimmed.trim_end())));
+            }
+
+            if self.new_line {
+                self.new_line = false;
+                tracker.start = tracker.pos - 1;
+                return Some(Lexeme::NewLine);
+            }
+
+            self.row_start = tracker.pos;
+            let (line, new_line) = tracker.line()?;
+            let line = line.strip_suffix('\r').unwrap_or(line);
+            self.row = Some(line).filter(|line| !line.trim().is_empty() && !self.starts.is_empty());
+            self.col = 0;
+            self.new_line = new_line;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Lexeme, Parser};
+
+    #[test]
+    fn parse_fixed_width() {
+        let input = "name   size  date\nfoo      12  May 1\nbar.rs  3    Jun 20\n";
+        let expected = [
+            ["name", "size", "date"],
+            ["foo", "12", "May 1"],
+            ["bar.rs", "3", "Jun 20"],
+        ];
+
+        let expected: Vec<_> = expected
+            .iter()
+            .flat_map(|row| {
+                row.iter()
+                    .map(|&cell| Lexeme::Cell(cell.into()))
+                    .chain(Some(Lexeme::NewLine))
+            })
+            .collect();
+
+        let parser = Parser::fixed_width(input, &[0, 7, 13]);
+        let parsed: Vec<_> = parser.map(Result::unwrap).collect();
+        assert_eq!(parsed, expected);
+
+        let parser = Parser::fixed_width_auto(input);
+        let parsed: Vec<_> = parser.map(Result::unwrap).collect();
+        assert_eq!(parsed, expected);
+    }
+}
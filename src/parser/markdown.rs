@@ -0,0 +1,148 @@
+use super::Tracker;
+use crate::Lexeme;
+use std::borrow::Cow;
+
+/// Returns the position of the first pipe that isn't escaped
+fn find_pipe(text: &str) -> Option<usize> {
+    let bytes = text.as_bytes();
+    let mut idx = 0;
+    while idx < bytes.len() {
+        match bytes[idx] {
+            b'\\' => idx += 2,
+            b'|' => return Some(idx),
+            _ => idx += 1,
+        }
+    }
+    None
+}
+
+/// Checks the row marks column alignments like `|:---|---:|`
+fn is_alignment(mut row: &str) -> bool {
+    loop {
+        let (cell, rest) = match find_pipe(row) {
+            Some(idx) => (&row[..idx], Some(&row[idx + 1..])),
+            None => (row, None),
+        };
+
+        let cell = cell.trim();
+        let dashes = cell.strip_prefix(':').unwrap_or(cell);
+        let dashes = dashes.strip_suffix(':').unwrap_or(dashes);
+        if dashes.is_empty() || !dashes.bytes().all(|b| b == b'-') {
+            return false;
+        }
+
+        match rest {
+            Some(rest) => row = rest,
+            None => return true,
+        }
+    }
+}
+
+fn unescape(cell: &str) -> Cow<'_, str> {
+    if cell.contains("\\|") {
+        Cow::Owned(cell.replace("\\|", "|"))
+    } else {
+        Cow::Borrowed(cell)
+    }
+}
+
+/// The state of parsing a Markdown pipe table
+#[derive(Default)]
+pub(super) struct Markdown<'a> {
+    /// Unread cells of the current row
+    row: Option<&'a str>,
+    /// The position where the unread cells end
+    row_end: usize,
+    /// The position where the line ends
+    line_end: usize,
+    /// The current row ends with a line break
+    new_line: bool,
+    /// The number of read rows
+    rows: usize,
+}
+
+impl<'a> Markdown<'a> {
+    pub(super) fn next(&mut self, tracker: &mut Tracker<'a>) -> Option<Lexeme<'a>> {
+        loop {
+            if let Some(row) = self.row {
+                let cell = match find_pipe(row) {
+                    Some(idx) => {
+                        self.row = Some(&row[idx + 1..]);
+                        &row[..idx]
+                    }
+                    None => {
+                        self.row = None;
+                        row
+                    }
+                };
+
+                let trimmed = cell.trim_start();
+                tracker.start = self.row_end - row.len() + cell.len() - trimmed.len();
+                return Some(Lexeme::Cell(unescape(trimmed.trim_end())));
+            }
+
+            if self.new_line {
+                self.new_line = false;
+                tracker.start = self.line_end;
+                return Some(Lexeme::NewLine);
+            }
+
+            let line_start = tracker.pos;
+            let (line, new_line) = tracker.line()?;
+            self.line_end = line_start + line.len();
+            let trimmed = line.trim_end();
+            let row_end = line_start + trimmed.len();
+            let line = trimmed.trim_start();
+            if line.is_empty() {
+                continue;
+            }
+
+            let line = line.strip_prefix('|').unwrap_or(line);
+            let (line, row_end) = match line.strip_suffix('|') {
+                Some(stripped) if !stripped.ends_with('\\') => (stripped, row_end - 1),
+                _ => (line, row_end),
+            };
+
+            // The alignment row follows the header
+            self.rows += 1;
+            if self.rows == 2 && is_alignment(line) {
+                continue;
+            }
+
+            self.row = Some(line);
+            self.row_end = row_end;
+            self.new_line = new_line;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Lexeme, Parser};
+
+    #[test]
+    fn parse_markdown() {
+        let parser = Parser::markdown(
+            "| a | b \\| c |\n\
+             |:--|----:|\n\
+             \n\
+             | 1 |  |\n\
+             2 | --- ",
+        );
+
+        let parsed: Vec<_> = parser.map(Result::unwrap).collect();
+        assert_eq!(
+            parsed,
+            [
+                Lexeme::Cell("a".into()),
+                Lexeme::Cell("b | c".into()),
+                Lexeme::NewLine,
+                Lexeme::Cell("1".into()),
+                Lexeme::Cell("".into()),
+                Lexeme::NewLine,
+                Lexeme::Cell("2".into()),
+                Lexeme::Cell("---".into()),
+            ]
+        );
+    }
+}
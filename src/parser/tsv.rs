@@ -0,0 +1,73 @@
+use super::Tracker;
+use crate::Lexeme;
+use std::borrow::Cow;
+
+/// The state of parsing tab-separated values
+#[derive(Default)]
+pub(super) struct Tsv<'a> {
+    /// Unread cells of the current row
+    row: Option<&'a str>,
+    /// The position where the unread cells end
+    row_end: usize,
+    /// The current row ends with a line break
+    new_line: bool,
+}
+
+impl<'a> Tsv<'a> {
+    pub(super) fn next(&mut self, tracker: &mut Tracker<'a>) -> Option<Lexeme<'a>> {
+        loop {
+            if let Some(row) = self.row {
+                tracker.start = self.row_end - row.len();
+                let cell = match row.find('\t') {
+                    Some(idx) => {
+                        self.row = Some(&row[idx + 1..]);
+                        &row[..idx]
+                    }
+                    None => {
+                        self.row = None;
+                        row
+                    }
+                };
+
+                return Some(Lexeme::Cell(Cow::Borrowed(cell)));
+            }
+
+            if self.new_line {
+                self.new_line = false;
+                tracker.start = self.row_end;
+                return Some(Lexeme::NewLine);
+            }
+
+            let line_start = tracker.pos;
+            let (line, new_line) = tracker.line()?;
+            let line = line.strip_suffix('\r').unwrap_or(line);
+            self.row = Some(line).filter(|line| !line.is_empty());
+            self.row_end = line_start + line.len();
+            self.new_line = new_line;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Lexeme, Parser};
+
+    #[test]
+    fn parse_tsv() {
+        let parser = Parser::tsv("\ta\t\tb\t\r\n\nc d");
+        let parsed: Vec<_> = parser.map(Result::unwrap).collect();
+        assert_eq!(
+            parsed,
+            [
+                Lexeme::Cell("".into()),
+                Lexeme::Cell("a".into()),
+                Lexeme::Cell("".into()),
+                Lexeme::Cell("b".into()),
+                Lexeme::Cell("".into()),
+                Lexeme::NewLine,
+                Lexeme::NewLine,
+                Lexeme::Cell("c d".into()),
+            ]
+        );
+    }
+}
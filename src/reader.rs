@@ -0,0 +1,89 @@
+use crate::Lexeme;
+use csv::{ReaderBuilder, StringRecord, StringRecordsIntoIter};
+use std::{borrow::Cow, io::Read};
+
+/// The adapter that reads CSV records with the `csv` crate
+/// and yields them as lexemes for [`Rows`](crate::Rows)
+pub struct CsvReader<R> {
+    records: StringRecordsIntoIter<R>,
+    record: Option<StringRecord>,
+    field: usize,
+}
+
+impl<R> CsvReader<R>
+where
+    R: Read,
+{
+    /// Reads comma separated records with `"` quotes
+    pub fn new(reader: R) -> Self {
+        Self::from_builder(ReaderBuilder::new(), reader)
+    }
+
+    /// Reads records with the delimiter, quote and other settings of the `builder`.
+    /// The header is read as the first row and rows may differ in length.
+    pub fn from_builder(mut builder: ReaderBuilder, reader: R) -> Self {
+        let records = builder
+            .has_headers(false)
+            .flexible(true)
+            .from_reader(reader)
+            .into_records();
+
+        Self {
+            records,
+            record: None,
+            field: 0,
+        }
+    }
+}
+
+impl<R> Iterator for CsvReader<R>
+where
+    R: Read,
+{
+    type Item = Result<Lexeme<'static>, csv::Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let record = match &self.record {
+            Some(record) => record,
+            None => match self.records.next()? {
+                Ok(record) => {
+                    self.field = 0;
+                    self.record.insert(record)
+                }
+                Err(err) => return Some(Err(err)),
+            },
+        };
+
+        match record.get(self.field) {
+            Some(field) => {
+                self.field += 1;
+                Some(Ok(Lexeme::Cell(Cow::Owned(field.to_owned()))))
+            }
+            None => {
+                self.record = None;
+                Some(Ok(Lexeme::NewLine))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Rows;
+
+    #[test]
+    fn read_csv() {
+        let input = "a;'b\nc'\n'd;e';f\n";
+        let mut builder = ReaderBuilder::new();
+        builder.delimiter(b';').quote(b'\'');
+
+        let lexemes = CsvReader::from_builder(builder, input.as_bytes()).map(Result::unwrap);
+        let mut head = Rows::new(lexemes).head();
+        assert_eq!(head.by_ref().collect::<Vec<_>>(), ["a", "b\nc"]);
+
+        let mut tail = head.into_tail();
+        assert_eq!(tail.row().unwrap().collect::<Vec<_>>(), ["d;e", "f"]);
+        assert!(tail.row().is_none());
+    }
+}
@@ -1,124 +1,228 @@
 use crate::Lexeme;
-use std::{cell::RefCell, iter::Peekable, rc::Rc};
+use std::{borrow::Cow, iter::Peekable, vec};
 
-struct Inner<I>
+/// The iterator wrapper for iterating over rows
+pub struct Rows<I>
 where
     I: Iterator,
 {
     iter: Peekable<I>,
     n_cols: usize,
+    /// The header is padded to at least this number of columns
+    min_cols: usize,
+    skip_blank: bool,
+    /// Data rows may be dropped before all cells are used
+    partial: bool,
+    /// The last data row was dropped before its line break
+    unfinished: bool,
 }
 
-impl<I> Inner<I>
+impl<I> Rows<I>
 where
     I: Iterator,
 {
-    fn new(iter: I) -> Self {
+    pub fn new<T>(iter: T) -> Self
+    where
+        T: IntoIterator<IntoIter = I>,
+    {
         Self {
-            iter: iter.peekable(),
+            iter: iter.into_iter().peekable(),
             n_cols: 0,
+            min_cols: 0,
+            skip_blank: false,
+            partial: false,
+            unfinished: false,
         }
     }
-}
 
-/// The iterator wrapper for iterating over rows
-pub struct Rows<I>
-where
-    I: Iterator,
-{
-    inner: Option<Inner<I>>,
+    /// Skips blank lines before the header and data rows without cells
+    /// instead of yielding rows of empty cells, so blank lines are only spacing
+    pub fn skip_blank_rows(mut self, enable: bool) -> Self {
+        self.skip_blank = enable;
+        self
+    }
+
+    /// Lets data rows be dropped before all cells are used, the rest of the row
+    /// is skipped. By default such a drop panics.
+    pub fn partial_rows(mut self, enable: bool) -> Self {
+        self.partial = enable;
+        self
+    }
+
+    /// Starts iterating over data rows of input without a header,
+    /// rows are padded or truncated to `n_cols` cells
+    pub fn tail(mut self, n_cols: usize) -> Tail<I> {
+        self.n_cols = n_cols;
+        Tail { rows: self }
+    }
 }
 
-impl<I> Rows<I>
-where
-    I: Iterator,
-{
-    pub fn new<T>(iter: T) -> Self
+impl<'a> Rows<vec::IntoIter<Lexeme<'a>>> {
+    /// Collects the lexemes to take the number of columns from the widest row
+    /// instead of the header. The header and shorter rows are padded with empty cells.
+    pub fn widest<T>(lexemes: T) -> Self
     where
-        T: IntoIterator<IntoIter = I>,
+        T: IntoIterator<Item = Lexeme<'a>>,
     {
+        let lexemes: Vec<_> = lexemes.into_iter().collect();
+        let mut widest = 0;
+        let mut cells = 0;
+        for lex in &lexemes {
+            match lex {
+                Lexeme::Cell(_) => {
+                    cells += 1;
+                    widest = cells.max(widest);
+                }
+                Lexeme::NewLine => cells = 0,
+                Lexeme::Comment(_) => (),
+            }
+        }
+
         Self {
-            inner: Some(Inner::new(iter.into_iter())),
+            min_cols: widest,
+            ..Self::new(lexemes)
         }
     }
+}
 
-    pub fn split(mut self) -> (Head<I>, Tail<I>) {
-        let inner = self.inner.take();
-        let rows = Rc::new(RefCell::new(self));
-        let head = Head {
-            rows: Rc::clone(&rows),
-            inner,
-        };
-        let tail = Tail { rows, inner: None };
-        (head, tail)
+impl<'a, I> Rows<I>
+where
+    I: Iterator<Item = Lexeme<'a>>,
+{
+    /// Starts iterating over the header row
+    pub fn head(mut self) -> Head<I> {
+        self.skip_to_row();
+        Head {
+            rows: self,
+            done: false,
+        }
+    }
+
+    /// Skips comments, so the next lexeme is a part of a row
+    fn skip_comments(&mut self) {
+        while let Some(Lexeme::Comment(_)) = self.iter.peek() {
+            self.iter.next();
+        }
+    }
+
+    /// Skips the rest of an unfinished row, comments and,
+    /// if enabled, line breaks of blank rows
+    fn skip_to_row(&mut self) {
+        if self.unfinished {
+            self.unfinished = false;
+            for lex in self.iter.by_ref() {
+                if let Lexeme::NewLine = lex {
+                    break;
+                }
+            }
+        }
+
+        loop {
+            self.skip_comments();
+            match self.iter.peek() {
+                Some(Lexeme::NewLine) if self.skip_blank => self.iter.next(),
+                _ => break,
+            };
+        }
     }
 }
 
+/// The iterator over the header row.
+/// The number of header cells sets the number of columns,
+/// missing cells of the header are empty.
 pub struct Head<I>
 where
     I: Iterator,
 {
-    rows: Rc<RefCell<Rows<I>>>,
-    inner: Option<Inner<I>>,
+    rows: Rows<I>,
+    done: bool,
+}
+
+impl<'a, I> Head<I>
+where
+    I: Iterator<Item = Lexeme<'a>>,
+{
+    /// Skips the rest of the header and
+    /// returns the iterator over data rows
+    pub fn into_tail(mut self) -> Tail<I> {
+        while self.next().is_some() {}
+        Tail { rows: self.rows }
+    }
 }
 
 impl<'a, I> Iterator for Head<I>
 where
     I: Iterator<Item = Lexeme<'a>>,
 {
-    type Item = &'a str;
+    type Item = Cow<'a, str>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        let inner = self.inner.as_mut().unwrap();
-        match inner.iter.next() {
-            Some(Lexeme::Cell(cell)) => {
-                inner.n_cols += 1;
-                Some(cell)
+        let rows = &mut self.rows;
+        if !self.done {
+            match rows.iter.next() {
+                Some(Lexeme::Cell(cell)) => {
+                    rows.n_cols += 1;
+                    return Some(cell);
+                }
+                Some(Lexeme::Comment(_)) => return self.next(),
+                Some(Lexeme::NewLine) | None => self.done = true,
             }
-            Some(Lexeme::NewLine) => None,
-            None => None,
         }
-    }
-}
 
-impl<I> Drop for Head<I>
-where
-    I: Iterator,
-{
-    fn drop(&mut self) {
-        let mut rows = self.rows.borrow_mut();
-        rows.inner = self.inner.take();
+        if rows.n_cols < rows.min_cols {
+            rows.n_cols += 1;
+            Some(Cow::Borrowed(""))
+        } else {
+            None
+        }
     }
 }
 
+/// The iterator over data rows
 pub struct Tail<I>
 where
     I: Iterator,
 {
-    rows: Rc<RefCell<Rows<I>>>,
-    inner: Option<Inner<I>>,
+    rows: Rows<I>,
 }
 
-impl<I> Tail<I>
+impl<'a, I> Tail<I>
 where
-    I: Iterator,
+    I: Iterator<Item = Lexeme<'a>>,
 {
-    pub fn row(&mut self) -> Option<TailRow<I>> {
-        let inner = match &mut self.inner {
-            None => {
-                let mut rows = self.rows.try_borrow_mut().expect("Use head iterator first");
-                self.inner = rows.inner.take();
-                self.inner.as_mut().unwrap()
+    pub fn row(&mut self) -> Option<TailRow<'_, I>> {
+        let cols_left = self.rows.n_cols;
+        self.rows.skip_to_row();
+        self.rows.iter.peek()?;
+
+        // A row without columns yields no cells, so it's skipped here
+        if cols_left == 0 {
+            for lex in self.rows.iter.by_ref() {
+                if let Lexeme::NewLine = lex {
+                    break;
+                }
             }
-            Some(inner) => inner,
-        };
+        }
 
-        let cols_left = inner.n_cols;
-        if inner.iter.peek().is_none() {
-            None
-        } else {
-            Some(TailRow::new(self, cols_left))
+        Some(TailRow::new(self, cols_left))
+    }
+
+    /// Skips the next data row and returns the number of its cells
+    /// as they are in the input, without padding or truncating
+    pub fn row_len(&mut self) -> Option<usize> {
+        self.rows.skip_to_row();
+        self.rows.iter.peek()?;
+
+        let mut len = 0;
+        for lex in self.rows.iter.by_ref() {
+            match lex {
+                Lexeme::Cell(_) => len += 1,
+                Lexeme::Comment(_) => (),
+                Lexeme::NewLine => break,
+            }
         }
+
+        Some(len)
     }
 }
 
@@ -128,6 +232,13 @@ enum TailRowState {
     Done,
 }
 
+/// The iterator over cells of a data row.
+/// It yields exactly as many cells as there are columns: extra cells of a wide row
+/// are skipped and a short row is padded, so the length is known up front.
+///
+/// The row must be fully used before it's dropped, otherwise the drop panics.
+/// To take only a part of the row, take it with `by_ref` and drain the rest
+/// or enable [`Rows::partial_rows`].
 pub struct TailRow<'t, I>
 where
     I: Iterator,
@@ -149,20 +260,20 @@ where
         }
     }
 
-    fn next(&mut self) -> Option<&'a str>
+    fn next(&mut self) -> Option<Cow<'a, str>>
     where
         I: Iterator<Item = Lexeme<'a>>,
     {
-        let inner = self.tail.inner.as_mut().unwrap();
+        let rows = &mut self.tail.rows;
         match self.state {
-            TailRowState::Iterate => match inner.iter.next() {
+            TailRowState::Iterate => match rows.iter.next() {
                 Some(Lexeme::Cell(cell)) => {
                     if self.cols_left == 1 {
                         // If iterating is not ended,
                         // iterate and ignore the rest part.
                         loop {
-                            match inner.iter.next() {
-                                Some(Lexeme::Cell(_)) => (),
+                            match rows.iter.next() {
+                                Some(Lexeme::Cell(_)) | Some(Lexeme::Comment(_)) => (),
                                 Some(Lexeme::NewLine) => break,
                                 None => break,
                             }
@@ -171,20 +282,21 @@ where
 
                     Some(cell)
                 }
-                Some(Lexeme::NewLine) => {
+                Some(Lexeme::Comment(_)) => Self::next(self),
+                // A short last row is padded like the rest
+                Some(Lexeme::NewLine) | None => {
                     if self.cols_left == 0 {
                         self.state = TailRowState::Done;
                         None
                     } else {
                         self.state = TailRowState::Default;
-                        Some("")
+                        Some(Cow::Borrowed(""))
                     }
                 }
-                None => None,
             },
             TailRowState::Default => match self.cols_left {
                 0 => None,
-                _ => Some(""),
+                _ => Some(Cow::Borrowed("")),
             },
             TailRowState::Done => None,
         }
@@ -195,7 +307,7 @@ impl<'a, I> Iterator for TailRow<'_, I>
 where
     I: Iterator<Item = Lexeme<'a>>,
 {
-    type Item = &'a str;
+    type Item = Cow<'a, str>;
 
     fn next(&mut self) -> Option<Self::Item> {
         if self.cols_left == 0 {
@@ -213,6 +325,8 @@ where
     }
 }
 
+/// The length counts yielded cells after wide rows are truncated,
+/// not the lexemes left in the underlying iterator
 impl<'a, I> ExactSizeIterator for TailRow<'_, I> where I: Iterator<Item = Lexeme<'a>> {}
 
 /// Drop implementation checks an iterator was fully used.
@@ -222,9 +336,19 @@ where
     I: Iterator,
 {
     fn drop(&mut self) {
-        if self.cols_left != 0 && !std::thread::panicking() {
+        if self.cols_left == 0 || std::thread::panicking() {
+            return;
+        }
+
+        let rows = &mut self.tail.rows;
+        if !rows.partial {
             panic!("The iterator must be fully used")
         }
+
+        // The line break isn't read yet, so the next row skips the rest
+        if let TailRowState::Iterate = self.state {
+            rows.unfinished = true;
+        }
     }
 }
 
@@ -235,26 +359,26 @@ mod tests {
     #[test]
     fn iterator() {
         let table = [
-            Lexeme::Cell("a"),
-            Lexeme::Cell("b"),
-            Lexeme::Cell("c"),
+            Lexeme::Cell("a".into()),
+            Lexeme::Cell("b".into()),
+            Lexeme::Cell("c".into()),
             Lexeme::NewLine,
-            Lexeme::Cell("0"),
-            Lexeme::Cell("1"),
+            Lexeme::Cell("0".into()),
+            Lexeme::Cell("1".into()),
             Lexeme::NewLine,
-            Lexeme::Cell("2"),
-            Lexeme::Cell("3"),
-            Lexeme::Cell("4"),
-            Lexeme::Cell("5"),
+            Lexeme::Cell("2".into()),
+            Lexeme::Cell("3".into()),
+            Lexeme::Cell("4".into()),
+            Lexeme::Cell("5".into()),
             Lexeme::NewLine,
             Lexeme::NewLine,
         ];
 
-        let rows = Rows::new(table);
-        let (head, mut tail) = rows.split();
+        let mut head = Rows::new(table).head();
+        let cells: Vec<_> = head.by_ref().collect();
+        assert_eq!(cells, ["a", "b", "c"]);
 
-        let head: Vec<_> = head.collect();
-        assert_eq!(head, ["a", "b", "c"]);
+        let mut tail = head.into_tail();
 
         if let Some(row) = tail.row() {
             let tail: Vec<_> = row.collect();
@@ -273,4 +397,141 @@ mod tests {
 
         assert!(tail.row().is_none());
     }
+
+    #[test]
+    fn widest() {
+        let table = [
+            Lexeme::Cell("a".into()),
+            Lexeme::NewLine,
+            Lexeme::Cell("0".into()),
+            Lexeme::Cell("1".into()),
+            Lexeme::Cell("2".into()),
+            Lexeme::NewLine,
+            Lexeme::Cell("3".into()),
+        ];
+
+        let mut head = Rows::widest(table).head();
+        let cells: Vec<_> = head.by_ref().collect();
+        assert_eq!(cells, ["a", "", ""]);
+
+        let mut tail = head.into_tail();
+        let row: Vec<_> = tail.row().unwrap().collect();
+        assert_eq!(row, ["0", "1", "2"]);
+
+        let row: Vec<_> = tail.row().unwrap().collect();
+        assert_eq!(row, ["3", "", ""]);
+        assert!(tail.row().is_none());
+    }
+
+    #[test]
+    fn no_columns() {
+        let table = [
+            Lexeme::NewLine,
+            Lexeme::Cell("a".into()),
+            Lexeme::Cell("b".into()),
+            Lexeme::NewLine,
+            Lexeme::Cell("c".into()),
+        ];
+
+        let mut tail = Rows::new(table).head().into_tail();
+        assert_eq!(tail.row().unwrap().count(), 0);
+        assert_eq!(tail.row().unwrap().count(), 0);
+        assert!(tail.row().is_none());
+    }
+
+    #[test]
+    fn row_len() {
+        let mut tail = Rows::new(wide_rows()).head().into_tail();
+        assert_eq!(tail.row_len(), Some(4));
+        assert_eq!(tail.row_len(), None);
+    }
+
+    #[test]
+    fn skip_blank_rows() {
+        let table = [
+            Lexeme::NewLine,
+            Lexeme::Cell("a".into()),
+            Lexeme::NewLine,
+            Lexeme::NewLine,
+            Lexeme::Cell("0".into()),
+            Lexeme::NewLine,
+            Lexeme::NewLine,
+            Lexeme::NewLine,
+            Lexeme::Cell("1".into()),
+            Lexeme::NewLine,
+            Lexeme::NewLine,
+        ];
+
+        let mut head = Rows::new(table).skip_blank_rows(true).head();
+        assert_eq!(head.by_ref().collect::<Vec<_>>(), ["a"]);
+
+        let mut tail = head.into_tail();
+        let mut rows = vec![];
+        while let Some(row) = tail.row() {
+            rows.push(row.collect::<Vec<_>>());
+        }
+
+        assert_eq!(rows, [["0"], ["1"]]);
+    }
+
+    fn wide_rows() -> [Lexeme<'static>; 8] {
+        [
+            Lexeme::Cell("a".into()),
+            Lexeme::Cell("b".into()),
+            Lexeme::NewLine,
+            Lexeme::Cell("0".into()),
+            Lexeme::Cell("1".into()),
+            Lexeme::Cell("2".into()),
+            Lexeme::Cell("3".into()),
+            Lexeme::NewLine,
+        ]
+    }
+
+    #[test]
+    fn take_wide_row() {
+        let mut tail = Rows::new(wide_rows()).head().into_tail();
+        let mut row = tail.row().unwrap();
+        assert_eq!(row.len(), 2);
+        assert_eq!(row.by_ref().take(1).collect::<Vec<_>>(), ["0"]);
+        assert_eq!(row.len(), 1);
+        assert_eq!(row.collect::<Vec<_>>(), ["1"]);
+        assert!(tail.row().is_none());
+    }
+
+    #[test]
+    #[should_panic(expected = "The iterator must be fully used")]
+    fn take_wide_row_partially() {
+        let mut tail = Rows::new(wide_rows()).head().into_tail();
+        let row: Vec<_> = tail.row().unwrap().take(1).collect();
+        assert_eq!(row, ["0"]);
+    }
+
+    #[test]
+    fn partial_rows() {
+        let mut tail = Rows::new(wide_rows()).partial_rows(true).head().into_tail();
+
+        let row: Vec<_> = tail.row().unwrap().take(1).collect();
+        assert_eq!(row, ["0"]);
+        assert!(tail.row().is_none());
+
+        let table = [
+            Lexeme::Cell("a".into()),
+            Lexeme::Cell("b".into()),
+            Lexeme::NewLine,
+            Lexeme::Cell("0".into()),
+            Lexeme::Cell("1".into()),
+            Lexeme::NewLine,
+            Lexeme::Cell("2".into()),
+            Lexeme::NewLine,
+        ];
+
+        let mut tail = Rows::new(table).partial_rows(true).head().into_tail();
+        drop(tail.row());
+        let row: Vec<_> = tail.row().unwrap().collect();
+        assert_eq!(row, ["2", ""]);
+
+        // The padded row has no lexemes left to skip
+        drop(tail.row());
+        assert!(tail.row().is_none());
+    }
 }
@@ -0,0 +1,178 @@
+use crate::{
+    align,
+    args::{Args, InputFormat},
+    args_error, clamp, columns_error, parse_error, read_error, write_output,
+};
+use std::{
+    borrow::Cow,
+    fs::File,
+    io::{BufRead, BufReader, Seek},
+};
+use tableflip_rust::{Format, Lexeme, Location, Parser, Table};
+
+/// Reads rows of an input file one at a time
+struct Records<'p> {
+    path: &'p str,
+    reader: BufReader<File>,
+    record: String,
+    /// The number of lines before the current record
+    line: usize,
+}
+
+impl<'p> Records<'p> {
+    fn open(path: &'p str) -> Self {
+        match File::open(path) {
+            Ok(file) => Self {
+                path,
+                reader: BufReader::new(file),
+                record: String::new(),
+                line: 0,
+            },
+            Err(err) => read_error(path, err),
+        }
+    }
+
+    /// Starts reading the file from the beginning
+    fn rewind(&mut self) {
+        if let Err(err) = self.reader.rewind() {
+            read_error(self.path, err);
+        }
+
+        self.record.clear();
+        self.line = 0;
+    }
+
+    /// Reads cells of the next row
+    fn next(&mut self) -> Option<Vec<Cow<'_, str>>> {
+        self.line += self.record.bytes().filter(|&b| b == b'\n').count();
+        self.record.clear();
+
+        // A quoted cell may span lines, so the row ends
+        // on a line break after an even number of quotes
+        let mut quotes = 0;
+        loop {
+            let start = self.record.len();
+            match self.reader.read_line(&mut self.record) {
+                Ok(0) => break,
+                Ok(_) => quotes += self.record[start..].bytes().filter(|&b| b == b'"').count(),
+                Err(err) => read_error(self.path, err),
+            }
+
+            if quotes % 2 == 0 {
+                break;
+            }
+        }
+
+        let (path, record, line) = (self.path, &self.record, self.line);
+        let mut lexemes = Parser::new(record)
+            .map(|res| match res {
+                Ok(lex) => lex,
+                Err(err) => {
+                    let mut loc = Location::new(record, err.position());
+                    loc.line += line;
+                    parse_error(path, loc, err)
+                }
+            })
+            .peekable();
+
+        // The end of the file may only have whitespace
+        lexemes.peek()?;
+
+        let cells = lexemes
+            .filter_map(|lex| match lex {
+                Lexeme::Cell(cell) => Some(cell),
+                Lexeme::NewLine | Lexeme::Comment(_) => None,
+            })
+            .collect();
+
+        Some(cells)
+    }
+}
+
+/// Fills missing cells of the row and drops extra ones like the rest of data rows
+fn fit(mut row: Vec<Cow<str>>, cols_len: usize) -> Vec<Cow<str>> {
+    row.resize(cols_len, Cow::Borrowed(""));
+    row
+}
+
+/// Renders input files in two passes, so only one row is in memory at a time.
+/// The first pass measures rows and the second one writes them.
+pub fn run<'a>(args: &'a Args, mut table: Table<'a>) {
+    if args.inputs.is_empty() || args.inputs.iter().any(|path| path == "-") {
+        args_error("--stream requires input files");
+    }
+
+    if args.format != Format::Text {
+        args_error("--stream supports only the text format");
+    }
+
+    if args.lint {
+        args_error("--lint can't be used with --stream");
+    }
+
+    if args.split {
+        args_error("--split can't be used with --stream");
+    }
+
+    if args.input_format != InputFormat::Quoted {
+        args_error("--stream supports only quoted input");
+    }
+
+    if args.code_fence {
+        args_error("--code-fence can't be used with --stream");
+    }
+
+    if args.comments {
+        args_error("--comments can't be used with --stream");
+    }
+
+    let mut inputs: Vec<_> = args.inputs.iter().map(|path| Records::open(path)).collect();
+    for (idx, records) in inputs.iter_mut().enumerate() {
+        let path = records.path;
+        let header = records.next().unwrap_or_default();
+        match &args.headers {
+            Some(headers) => {
+                // The input has no header, so its first row is data
+                if header.len() != headers.len() {
+                    columns_error(path, headers.len(), header.len());
+                }
+
+                if idx == 0 {
+                    table = table.head(headers.iter().map(String::as_str));
+                }
+
+                table = table.measure(header);
+            }
+            None if idx == 0 => table = table.head(header.into_iter().map(Cow::into_owned)),
+            None => {
+                if header.len() != table.cols_len() {
+                    columns_error(path, table.cols_len(), header.len());
+                }
+            }
+        }
+
+        let cols_len = table.cols_len();
+        while let Some(row) = records.next() {
+            table = table.measure(fit(row, cols_len));
+        }
+    }
+
+    let table = clamp(align(table, args), args);
+    write_output(args.output.as_deref(), |out| {
+        let cols_len = table.cols_len();
+        let mut stream = table.stream();
+        stream.head(&mut *out)?;
+        for records in &mut inputs {
+            records.rewind();
+            if args.headers.is_none() {
+                records.next();
+            }
+
+            while let Some(row) = records.next() {
+                stream.row(&mut *out, fit(row, cols_len))?;
+            }
+        }
+
+        stream.finish(out)
+    });
+}
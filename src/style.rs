@@ -0,0 +1,78 @@
+/// The border style of text tables
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub enum Style {
+    /// The Markdown pipe table
+    #[default]
+    Markdown,
+    /// Box-drawing characters
+    Unicode,
+    /// Box-drawing characters with rounded corners
+    Rounded,
+    /// Aligned columns without borders
+    Plain,
+}
+
+/// Borders around cells of a line
+pub(crate) struct Borders {
+    pub left: &'static str,
+    pub inner: &'static str,
+    pub right: &'static str,
+}
+
+/// A horizontal line between rows
+pub(crate) struct Rule {
+    pub left: &'static str,
+    pub fill: char,
+    pub cross: &'static str,
+    pub right: &'static str,
+}
+
+impl Style {
+    pub(crate) fn borders(self) -> Borders {
+        let (left, inner, right) = match self {
+            Self::Markdown => ("|", "|", "|"),
+            Self::Unicode | Self::Rounded => ("│", "│", "│"),
+            Self::Plain => ("", "", ""),
+        };
+
+        Borders { left, inner, right }
+    }
+
+    /// The rule above the header
+    pub(crate) fn top(self) -> Option<Rule> {
+        match self {
+            Self::Unicode => Some(Rule::new("┌", '─', "┬", "┐")),
+            Self::Rounded => Some(Rule::new("╭", '─', "┬", "╮")),
+            Self::Markdown | Self::Plain => None,
+        }
+    }
+
+    /// The rule between the header and data rows
+    pub(crate) fn separator(self) -> Option<Rule> {
+        match self {
+            Self::Markdown => Some(Rule::new("|", '-', "|", "|")),
+            Self::Unicode | Self::Rounded => Some(Rule::new("├", '─', "┼", "┤")),
+            Self::Plain => None,
+        }
+    }
+
+    /// The rule below the last row
+    pub(crate) fn bottom(self) -> Option<Rule> {
+        match self {
+            Self::Unicode => Some(Rule::new("└", '─', "┴", "┘")),
+            Self::Rounded => Some(Rule::new("╰", '─', "┴", "╯")),
+            Self::Markdown | Self::Plain => None,
+        }
+    }
+}
+
+impl Rule {
+    fn new(left: &'static str, fill: char, cross: &'static str, right: &'static str) -> Self {
+        Self {
+            left,
+            fill,
+            cross,
+            right,
+        }
+    }
+}
@@ -1,9 +1,251 @@
-use std::fmt;
+#[cfg(feature = "serde")]
+mod data;
+mod render;
+
+pub use self::render::{Stream, TableWriter};
+
+use crate::{
+    ansi, Align, Ansi, Color, CountMismatch, Format, Lexeme, OutOfRange, RowLenError, Rows, Style,
+};
+use std::{
+    borrow::Cow,
+    collections::{HashMap, HashSet},
+    io,
+    iter::FromIterator,
+};
+
+/// Parses the cell as a finite number with the given decimal separator,
+/// the group separator of thousands is skipped unless it's the decimal one
+fn number(cell: &str, separator: char, group: Option<char>) -> Option<f64> {
+    let cell = cell.trim();
+    let ungrouped;
+    let cell = match group {
+        Some(group) if group != separator && cell.contains(group) => {
+            ungrouped = cell.replace(group, "");
+            &ungrouped
+        }
+        _ => cell,
+    };
+
+    let cell = match separator {
+        '.' => Cow::Borrowed(cell),
+        _ if cell.contains('.') => return None,
+        _ => Cow::Owned(cell.replacen(separator, ".", 1)),
+    };
+
+    cell.parse().ok().filter(|n: &f64| n.is_finite())
+}
+
+/// Parses the cell as a boolean like `true`, `yes` or `1`
+fn boolean(cell: &str) -> Option<bool> {
+    match cell.trim().to_ascii_lowercase().as_str() {
+        "true" | "yes" | "1" => Some(true),
+        "false" | "no" | "0" => Some(false),
+        _ => None,
+    }
+}
+
+/// Returns the width of the text in chars without escape sequences,
+/// tabs are expanded if `tab_width` is set
+fn text_width(text: &str, tab_width: Option<usize>) -> usize {
+    match tab_width {
+        Some(tab_width) => ansi::visible_chars(text).fold(0, |width, ch| match ch {
+            '\t' => next_tab_stop(width, tab_width),
+            _ => width + 1,
+        }),
+        None => ansi::display_width(text),
+    }
+}
+
+/// Returns the number of leading and trailing spaces
+fn edge_spaces(text: &str) -> usize {
+    let start = text.trim_start_matches(' ');
+    let trimmed = start.trim_end_matches(' ');
+    text.len() - trimmed.len()
+}
+
+/// Writes leading and trailing spaces as `&nbsp;`, so Markdown keeps them
+fn encode_spaces(text: &str) -> Cow<'_, str> {
+    if edge_spaces(text) == 0 {
+        return Cow::Borrowed(text);
+    }
+
+    let start = text.trim_start_matches(' ');
+    let trimmed = start.trim_end_matches(' ');
+    let mut encoded = "&nbsp;".repeat(text.len() - start.len());
+    encoded.push_str(trimmed);
+    encoded.push_str(&"&nbsp;".repeat(start.len() - trimmed.len()));
+    Cow::Owned(encoded)
+}
+
+/// How cells are measured in the text format
+#[derive(Copy, Clone)]
+struct Metrics {
+    tab_width: Option<usize>,
+    /// Leading and trailing spaces are written as `&nbsp;`
+    nbsp: bool,
+}
+
+impl Metrics {
+    fn width(self, text: &str) -> usize {
+        let nbsp = if self.nbsp { edge_spaces(text) } else { 0 };
+        text_width(text, self.tab_width) + nbsp * ("&nbsp;".len() - 1)
+    }
+}
+
+fn next_tab_stop(width: usize, tab_width: usize) -> usize {
+    match tab_width {
+        0 => width,
+        _ => (width / tab_width + 1) * tab_width,
+    }
+}
+
+/// Replaces tabs with spaces up to the next tab stop
+fn expand_tabs(text: &str, tab_width: usize) -> Cow<'_, str> {
+    if !text.contains('\t') {
+        return Cow::Borrowed(text);
+    }
+
+    let mut expanded = String::with_capacity(text.len());
+    let mut width = 0;
+    for ch in text.chars() {
+        match ch {
+            '\t' => {
+                let stop = next_tab_stop(width, tab_width);
+                expanded.push_str(&" ".repeat(stop - width));
+                width = stop;
+            }
+            _ => {
+                expanded.push(ch);
+                width += 1;
+            }
+        }
+    }
+    Cow::Owned(expanded)
+}
+
+/// Groups digits of the integer by thousands, returns `None` if it isn't an integer
+fn group_digits(cell: &str, separator: char) -> Option<String> {
+    let cell = cell.trim();
+    let digits = cell.trim_start_matches(&['-', '+'][..]);
+    if digits.is_empty()
+        || !digits.bytes().all(|b| b.is_ascii_digit())
+        || cell.len() - digits.len() > 1
+    {
+        return None;
+    }
+
+    let mut grouped = cell[..cell.len() - digits.len()].to_owned();
+    let lead = digits.len() % 3;
+    for (idx, digit) in digits.chars().enumerate() {
+        if idx != 0 && idx % 3 == lead {
+            grouped.push(separator);
+        }
+
+        grouped.push(digit);
+    }
+    Some(grouped)
+}
+
+/// Escapes HTML special chars, the text is borrowed if there are none
+fn escape_html(text: &str) -> Cow<'_, str> {
+    if !text.contains(&['<', '>', '&', '"'][..]) {
+        return Cow::Borrowed(text);
+    }
+
+    let mut escaped = String::with_capacity(text.len() + 8);
+    for ch in text.chars() {
+        match ch {
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '&' => escaped.push_str("&amp;"),
+            '"' => escaped.push_str("&quot;"),
+            _ => escaped.push(ch),
+        }
+    }
+    Cow::Owned(escaped)
+}
+
+/// Reorders options of columns like `Table::permute` does with cells,
+/// columns without an option get the fill
+fn permute_cols<T>(options: &mut Vec<T>, cols: &[usize], fill: T)
+where
+    T: Clone,
+{
+    if options.is_empty() {
+        return;
+    }
+
+    *options = cols
+        .iter()
+        .map(|&col| options.get(col).unwrap_or(&fill).clone())
+        .collect();
+}
+
+/// Passes the lexemes through and collects text of comments
+fn keep_comments<'a, 'c, I>(
+    lexemes: I,
+    comments: &'c mut Vec<Cow<'a, str>>,
+) -> impl Iterator<Item = Lexeme<'a>> + 'c
+where
+    I: IntoIterator<Item = Lexeme<'a>>,
+    I::IntoIter: 'c,
+{
+    lexemes.into_iter().inspect(move |lex| {
+        if let Lexeme::Comment(text) = lex {
+            comments.push(text.clone());
+        }
+    })
+}
 
 pub struct Table<'a> {
     cols_width: Vec<usize>,
-    rows: Vec<&'a str>,
+    rows: Vec<Cow<'a, str>>,
     rows_len: usize,
+    /// Numbers of cells of data rows before padding,
+    /// empty if every row is full
+    lens: Vec<usize>,
+    ragged: bool,
+    /// Data rows accounted by `measure` but not stored
+    measured: usize,
+    /// Widths of columns over the rows accounted by `measure`
+    measured_widths: Vec<usize>,
+    limit: Option<usize>,
+    row_numbers: bool,
+    format: Format,
+    align: Align,
+    aligns: Vec<Align>,
+    /// Alignments of single cells by the row and column
+    cell_aligns: HashMap<(usize, usize), Align>,
+    decimal_cols: Vec<bool>,
+    decimal_separator: char,
+    group_separator: Option<char>,
+    style: Style,
+    header_style: Ansi,
+    col_colors: Vec<Option<Color>>,
+    /// Glyphs of true and false values of columns
+    bool_glyphs: Vec<Option<(Cow<'a, str>, Cow<'a, str>)>>,
+    /// The text of empty data cells
+    placeholder: Option<Cow<'a, str>>,
+    highlight: Option<usize>,
+    outer_border: bool,
+    padding: usize,
+    tab_width: Option<usize>,
+    max_width: Option<usize>,
+    max_widths: Vec<Option<usize>>,
+    fixed_widths: Vec<usize>,
+    comments: Vec<Cow<'a, str>>,
+    ellipsis: Cow<'a, str>,
+    trim_trailing: bool,
+    pad_char: char,
+    prefix: Cow<'a, str>,
+    nbsp: bool,
+    /// The least number of dashes in a cell of the Markdown separator line
+    min_dashes: usize,
+    code_fence: bool,
+    trailing_newline: bool,
+    crlf: bool,
+    bom: bool,
 }
 
 impl<'a> Table<'a> {
@@ -12,101 +254,2646 @@ impl<'a> Table<'a> {
             cols_width: vec![],
             rows: vec![],
             rows_len: 0,
+            lens: vec![],
+            ragged: false,
+            measured: 0,
+            measured_widths: vec![],
+            limit: None,
+            row_numbers: false,
+            format: Format::Text,
+            align: Align::Left,
+            aligns: vec![],
+            cell_aligns: HashMap::new(),
+            decimal_cols: vec![],
+            decimal_separator: '.',
+            group_separator: None,
+            style: Style::Markdown,
+            header_style: Ansi::default(),
+            col_colors: vec![],
+            bool_glyphs: vec![],
+            placeholder: None,
+            highlight: None,
+            outer_border: true,
+            padding: 1,
+            tab_width: None,
+            max_width: None,
+            max_widths: vec![],
+            fixed_widths: vec![],
+            comments: vec![],
+            ellipsis: Cow::Borrowed("…"),
+            trim_trailing: false,
+            pad_char: ' ',
+            prefix: Cow::Borrowed(""),
+            nbsp: false,
+            min_dashes: 3,
+            code_fence: false,
+            trailing_newline: true,
+            crlf: false,
+            bom: false,
+        }
+    }
+
+    /// Creates the table from the header and data rows
+    pub fn from_rows<H, R, S>(header: H, rows: R) -> Self
+    where
+        H: IntoIterator<Item = S>,
+        R: IntoIterator,
+        R::Item: IntoIterator<Item = S>,
+        <R::Item as IntoIterator>::IntoIter: ExactSizeIterator,
+        S: Into<Cow<'a, str>>,
+    {
+        rows.into_iter()
+            .fold(Self::new().head(header), |table, row| table.tail(row))
+    }
+
+    /// Creates the table from the lexemes, the first row is the header.
+    /// Data rows are padded with empty cells or truncated to the number of columns.
+    /// Comments are kept and printed above the table.
+    pub fn from_lexemes<I>(lexemes: I) -> Self
+    where
+        I: IntoIterator<Item = Lexeme<'a>>,
+    {
+        let mut comments = vec![];
+        let mut table = {
+            let mut head = Rows::new(keep_comments(lexemes, &mut comments)).head();
+            let mut table = Self::new().head(head.by_ref());
+
+            let mut tail = head.into_tail();
+            while let Some(row) = tail.row() {
+                table = table.tail(row);
+            }
+            table
+        };
+
+        table.comments.extend(comments);
+        table
+    }
+
+    /// Creates a table from every part of the lexemes between blank lines,
+    /// the first row of each part is its header
+    pub fn split_lexemes<I>(lexemes: I) -> Vec<Self>
+    where
+        I: IntoIterator<Item = Lexeme<'a>>,
+    {
+        let mut tables = vec![];
+        let mut part = vec![];
+        let mut cells = false;
+        let mut line_start = true;
+        for lex in lexemes {
+            match lex {
+                Lexeme::NewLine if line_start => {
+                    if cells {
+                        tables.push(Self::from_lexemes(part.drain(..)));
+                    }
+
+                    part.clear();
+                    cells = false;
+                    continue;
+                }
+                Lexeme::NewLine => line_start = true,
+                Lexeme::Cell(_) => {
+                    cells = true;
+                    line_start = false;
+                }
+                Lexeme::Comment(_) => (),
+            }
+
+            part.push(lex);
+        }
+
+        if cells {
+            tables.push(Self::from_lexemes(part));
         }
+
+        tables
     }
 
-    pub fn head<H>(mut self, header: H) -> Self
+    pub fn head<H, S>(mut self, header: H) -> Self
     where
-        H: IntoIterator<Item = &'a str>,
+        H: IntoIterator<Item = S>,
+        S: Into<Cow<'a, str>>,
     {
         assert!(self.rows.is_empty());
-        self.rows = header.into_iter().collect();
-        self.cols_width = self.rows.iter().map(|row| row.chars().count()).collect();
+        self.rows = header.into_iter().map(Into::into).collect();
+        let metrics = self.metrics();
+        self.cols_width = self.rows.iter().map(|row| metrics.width(row)).collect();
+        self
+    }
+
+    /// Sets the header from labels separated by the delimiter, labels are trimmed
+    pub fn header_from_str(self, header: &'a str, delimiter: char) -> Self {
+        self.head(header.split(delimiter).map(str::trim))
+    }
+
+    /// Adds every row of the lexemes as a data row, the input has no header.
+    /// Rows are padded with empty cells or truncated to the number of columns.
+    /// Comments are kept and printed above the table.
+    pub fn tail_lexemes<I>(mut self, lexemes: I) -> Self
+    where
+        I: IntoIterator<Item = Lexeme<'a>>,
+    {
+        let mut comments = vec![];
+        {
+            let cols_len = self.cols_len();
+            let mut tail = Rows::new(keep_comments(lexemes, &mut comments)).tail(cols_len);
+            while let Some(row) = tail.row() {
+                self = self.tail(row);
+            }
+        }
+
+        self.comments.extend(comments);
         self
     }
 
-    pub fn tail<R>(mut self, row: R) -> Self
+    pub fn tail<R, S>(mut self, row: R) -> Self
     where
-        R: IntoIterator<Item = &'a str>,
+        R: IntoIterator<Item = S>,
         R::IntoIter: ExactSizeIterator,
+        S: Into<Cow<'a, str>>,
     {
         let row = row.into_iter();
-        assert_eq!(row.len(), self.cols_len());
+        if row.len() != self.cols_len() {
+            panic!("{}", self.row_len_error(row));
+        }
+
+        // A table without columns has nothing to render, so the row is dropped
+        if self.cols_len() == 0 {
+            return self;
+        }
+
+        self.rows.reserve(row.len());
         self.rows_len += 1;
+        if !self.lens.is_empty() {
+            self.lens.push(row.len());
+        }
 
+        let metrics = self.metrics();
         for (idx, cell) in row.enumerate() {
+            let cell = cell.into();
+            let cell_width = metrics.width(self.shown_cell(idx, &cell));
             let width = &mut self.cols_width[idx];
-            *width = cell.chars().count().max(*width);
+            *width = cell_width.max(*width);
             self.rows.push(cell);
         }
 
         self
     }
 
-    pub fn cols_len(&self) -> usize {
-        self.cols_width.len()
+    /// Adds the data row padded with empty cells or truncated to the number of columns
+    pub fn tail_lenient<R, S>(self, row: R) -> Self
+    where
+        R: IntoIterator<Item = S>,
+        S: Into<Cow<'a, str>>,
+    {
+        let cols_len = self.cols_len();
+        let mut row: Vec<_> = row.into_iter().take(cols_len).map(Into::into).collect();
+        row.resize(cols_len, Cow::Borrowed(""));
+        self.tail(row)
     }
 
-    pub fn rows_len(&self) -> usize {
-        self.rows_len
+    /// Adds the data row, fails if its length isn't the number of columns.
+    /// The error holds the index the row would have among data rows.
+    pub fn try_tail<R, S>(self, row: R) -> Result<Self, RowLenError>
+    where
+        R: IntoIterator<Item = S>,
+        R::IntoIter: ExactSizeIterator,
+        S: Into<Cow<'a, str>>,
+    {
+        let row = row.into_iter();
+        if row.len() != self.cols_len() {
+            return Err(self.row_len_error(row));
+        }
+
+        Ok(self.tail(row))
     }
-}
 
-impl fmt::Display for Table<'_> {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        if self.rows.is_empty() {
-            return Ok(());
+    /// Creates the error of the row with a wrong number of cells
+    fn row_len_error<R, S>(&self, row: R) -> RowLenError
+    where
+        R: ExactSizeIterator<Item = S>,
+        S: Into<Cow<'a, str>>,
+    {
+        RowLenError {
+            row: self.rows_len(),
+            expected: self.cols_len(),
+            provided: row.len(),
+            cells: row.map(|cell| cell.into().into_owned()).collect(),
         }
+    }
 
-        let mut rows = self.rows.chunks(self.cols_len());
-        let header = rows.next().unwrap();
-        for (cell, width) in header.iter().zip(&self.cols_width) {
-            write!(f, "| {:width$} ", cell, width = width)?;
+    /// Adds data rows until one has a wrong length, like [`Table::try_tail`]
+    pub fn extend_rows<I, R, S>(mut self, rows: I) -> Result<Self, RowLenError>
+    where
+        I: IntoIterator<Item = R>,
+        R: IntoIterator<Item = S>,
+        R::IntoIter: ExactSizeIterator,
+        S: Into<Cow<'a, str>>,
+    {
+        for row in rows {
+            self = self.try_tail(row)?;
         }
-        writeln!(f, "|")?;
 
-        if self.rows_len() == 1 {
-            return Ok(());
+        Ok(self)
+    }
+
+    /// Adds data rows of the other table below data rows of this one,
+    /// the header of the other table is dropped
+    pub fn append(mut self, other: Table<'a>) -> Result<Self, CountMismatch> {
+        let cols_len = self.cols_len();
+        if other.cols_len() != cols_len {
+            return Err(CountMismatch {
+                expected: cols_len,
+                provided: other.cols_len(),
+            });
         }
 
-        for &width in &self.cols_width {
-            write!(f, "|")?;
-            for _ in 0..width + 2 {
-                write!(f, "-")?;
+        if !self.lens.is_empty() || !other.lens.is_empty() {
+            self.lens.resize(self.rows_len, cols_len);
+            if other.lens.is_empty() {
+                self.lens.resize(self.rows_len + other.rows_len, cols_len);
+            } else {
+                self.lens.extend(other.lens);
             }
         }
-        writeln!(f, "|")?;
 
-        for row in rows {
-            for (cell, width) in row.iter().zip(&self.cols_width) {
-                write!(f, "| {:width$} ", cell, width = width)?;
+        for (width, &other) in self.cols_width.iter_mut().zip(&other.cols_width) {
+            *width = other.max(*width);
+        }
+
+        if self.measured_widths.len() < other.measured_widths.len() {
+            self.measured_widths.resize(other.measured_widths.len(), 0);
+        }
+
+        for (width, &other) in self.measured_widths.iter_mut().zip(&other.measured_widths) {
+            *width = other.max(*width);
+        }
+
+        self.rows.extend(other.rows.into_iter().skip(cols_len));
+        self.rows_len += other.rows_len;
+        self.measured += other.measured;
+        Ok(self)
+    }
+
+    /// Adds the data row that may be shorter than the header.
+    /// Missing cells are empty unless the table is rendered with `ragged`.
+    pub fn tail_ragged<R, S>(mut self, row: R) -> Self
+    where
+        R: IntoIterator<Item = S>,
+        S: Into<Cow<'a, str>>,
+    {
+        let cols_len = self.cols_len();
+        let mut row: Vec<_> = row.into_iter().map(Into::into).collect();
+        let len = row.len();
+        assert!(len <= cols_len);
+
+        row.resize(cols_len, Cow::Borrowed(""));
+        self = self.tail(row);
+        if len < cols_len {
+            self.lens.resize(self.rows_len, cols_len);
+            self.lens[self.rows_len - 1] = len;
+        }
+
+        self
+    }
+
+    /// Accounts for the width of the data row without storing it,
+    /// so the row can be written later by [`Table::stream`]
+    pub fn measure<R, S>(mut self, row: R) -> Self
+    where
+        R: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let mut len = 0;
+        let metrics = self.metrics();
+        self.measured_widths.resize(self.cols_len(), 0);
+        for (idx, cell) in row.into_iter().enumerate() {
+            let cell_width = metrics.width(self.shown_cell(idx, cell.as_ref()));
+            let width = &mut self.measured_widths[idx];
+            *width = cell_width.max(*width);
+            let width = &mut self.cols_width[idx];
+            *width = cell_width.max(*width);
+            len += 1;
+        }
+
+        assert_eq!(len, self.cols_len());
+        if len != 0 {
+            self.measured += 1;
+        }
+
+        self
+    }
+
+    /// Reserves capacity for at least `additional` more data rows
+    pub fn reserve(mut self, additional: usize) -> Self {
+        self.rows.reserve(additional * self.cols_len());
+        self
+    }
+
+    /// Sorts data rows by the cells of the given column, the header stays in place.
+    /// If every data cell of the column is a number, rows are compared numerically,
+    /// otherwise lexicographically. The sort is stable.
+    pub fn sort_by_col(mut self, col: usize, ascending: bool) -> Result<Self, OutOfRange> {
+        let cols_len = self.cols_len();
+        if col >= cols_len {
+            return Err(OutOfRange {
+                index: col,
+                len: cols_len,
+            });
+        }
+
+        let (header, data) = self.rows.split_at(cols_len);
+        let mut rows: Vec<_> = data.chunks(cols_len).enumerate().collect();
+        let (separator, group) = (self.decimal_separator, self.group_separator);
+        let numeric = rows
+            .iter()
+            .all(|(_, row)| number(&row[col], separator, group).is_some());
+
+        rows.sort_by(|(_, a), (_, b)| {
+            let ord = if numeric {
+                let a = number(&a[col], separator, group).unwrap();
+                let b = number(&b[col], separator, group).unwrap();
+                a.total_cmp(&b)
+            } else {
+                a[col].cmp(&b[col])
+            };
+
+            if ascending {
+                ord
+            } else {
+                ord.reverse()
             }
-            writeln!(f, "|")?;
+        });
+
+        if !self.lens.is_empty() {
+            self.lens = rows.iter().map(|&(idx, _)| self.lens[idx]).collect();
         }
 
-        Ok(())
+        let order: Vec<_> = rows.iter().map(|&(idx, _)| idx).collect();
+        self.rows = header
+            .iter()
+            .chain(rows.into_iter().flat_map(|(_, row)| row))
+            .cloned()
+            .collect();
+
+        self.reorder_cell_aligns(&order);
+        Ok(self)
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+    /// Removes data rows equal to the previous one, the header stays in place
+    pub fn dedup(self) -> Self {
+        self.dedup_rows(false)
+    }
 
-    #[test]
-    fn to_string() {
-        let table = Table::new()
-            .head(["one", "two", "three"])
-            .tail(["four", "five", "six"])
-            .tail(["seven", "eight", "nine"]);
+    /// Removes data rows equal to any previous one, the header stays in place
+    pub fn dedup_all(self) -> Self {
+        self.dedup_rows(true)
+    }
 
-        assert_eq!(
-            table.to_string(),
-            "\
-            | one   | two   | three |\n\
-            |-------|-------|-------|\n\
-            | four  | five  | six   |\n\
-            | seven | eight | nine  |\n\
-            "
+    fn dedup_rows(mut self, all: bool) -> Self {
+        let cols_len = self.cols_len();
+        if cols_len == 0 {
+            return self;
+        }
+
+        let (header, data) = self.rows.split_at(cols_len);
+        let mut seen = HashSet::new();
+        let mut rows: Vec<&[Cow<str>]> = vec![];
+        let mut lens = vec![];
+        let mut order = vec![];
+        for (idx, row) in data.chunks(cols_len).enumerate() {
+            let duplicate = if all {
+                !seen.insert(row)
+            } else {
+                rows.last() == Some(&row)
+            };
+
+            if !duplicate {
+                rows.push(row);
+                lens.extend(self.lens.get(idx));
+                order.push(idx);
+            }
+        }
+
+        self.lens = lens;
+        let rows_len = rows.len();
+        self.rows = header
+            .iter()
+            .chain(rows.into_iter().flatten())
+            .cloned()
+            .collect();
+
+        self.reorder_cell_aligns(&order);
+        self.rows_len = rows_len;
+        self.update_widths(cols_len);
+        self
+    }
+
+    /// Separates groups of thousands in integer data cells of the column,
+    /// like `1000000` to `1,000,000`. Other cells are left as is.
+    pub fn group_digits(mut self, col: usize, separator: char) -> Result<Self, OutOfRange> {
+        let cols_len = self.cols_len();
+        if col >= cols_len {
+            return Err(OutOfRange {
+                index: col,
+                len: cols_len,
+            });
+        }
+
+        for row in self.rows.chunks_mut(cols_len).skip(1) {
+            if let Some(grouped) = group_digits(&row[col], separator) {
+                row[col] = Cow::Owned(grouped);
+            }
+        }
+
+        self.update_widths(cols_len);
+        Ok(self)
+    }
+
+    /// Swaps rows and columns. The header becomes the first column,
+    /// so the first column, including its header cell, becomes the new header.
+    /// Options set per column are cleared, since the columns hold other data.
+    pub fn transpose(mut self) -> Self {
+        let cols_len = self.cols_len();
+        if cols_len == 0 {
+            return self;
+        }
+
+        let rows_len = self.rows_len + 1;
+        let rows = (0..cols_len)
+            .flat_map(|col| (0..rows_len).map(move |row| row * cols_len + col))
+            .map(|idx| self.rows[idx].clone())
+            .collect();
+
+        // Lengths of short rows don't match the new columns
+        self.lens.clear();
+        self.aligns.clear();
+        self.decimal_cols.clear();
+        self.col_colors.clear();
+        self.bool_glyphs.clear();
+        self.max_widths.clear();
+        self.fixed_widths.clear();
+        self.measured_widths.clear();
+        self.cell_aligns = self
+            .cell_aligns
+            .drain()
+            .map(|((row, col), align)| ((col, row), align))
+            .collect();
+
+        self.rows = rows;
+        self.rows_len = cols_len - 1;
+        self.update_widths(rows_len);
+        self
+    }
+
+    /// Removes columns whose header and data cells are all empty
+    pub fn drop_empty_columns(mut self) -> Self {
+        let cols_len = self.cols_len();
+        let cols: Vec<_> = (0..cols_len)
+            .filter(|&col| self.rows.chunks(cols_len).any(|row| !row[col].is_empty()))
+            .collect();
+
+        self.permute(&cols);
+        self
+    }
+
+    /// Keeps only the given columns in the given order.
+    /// A column may be listed several times.
+    pub fn select(mut self, cols: &[usize]) -> Result<Self, OutOfRange> {
+        let cols_len = self.cols_len();
+        if let Some(&col) = cols.iter().find(|&&col| col >= cols_len) {
+            return Err(OutOfRange {
+                index: col,
+                len: cols_len,
+            });
+        }
+
+        self.permute(cols);
+        Ok(self)
+    }
+
+    /// Orders columns by their header labels, columns with equal labels keep their order.
+    /// Labels are compared by chars, so it's case-sensitive and `B` goes before `a`.
+    pub fn sort_columns(mut self) -> Self {
+        let mut cols: Vec<_> = (0..self.cols_len()).collect();
+        cols.sort_by(|&a, &b| self.rows[a].cmp(&self.rows[b]));
+        self.permute(&cols);
+        self
+    }
+
+    /// Rebuilds every row and options of columns from the given columns
+    fn permute(&mut self, cols: &[usize]) {
+        let cols_len = self.cols_len();
+        if cols_len != 0 {
+            self.rows = self
+                .rows
+                .chunks(cols_len)
+                .flat_map(|row| cols.iter().map(move |&col| row[col].clone()))
+                .collect();
+        }
+
+        permute_cols(&mut self.aligns, cols, self.align);
+        permute_cols(&mut self.decimal_cols, cols, false);
+        permute_cols(&mut self.col_colors, cols, None);
+        permute_cols(&mut self.bool_glyphs, cols, None);
+        permute_cols(&mut self.max_widths, cols, None);
+        permute_cols(&mut self.fixed_widths, cols, 0);
+        permute_cols(&mut self.measured_widths, cols, 0);
+        self.cell_aligns = self
+            .cell_aligns
+            .iter()
+            .flat_map(|(&(row, col), &align)| {
+                let moved = cols.iter().enumerate().filter(move |&(_, &old)| old == col);
+                moved.map(move |(new, _)| ((row, new), align))
+            })
+            .collect();
+
+        self.lens.clear();
+        self.update_widths(cols.len());
+    }
+
+    /// Adds a comment line printed as `#` and the text above the table in the text format
+    pub fn comment<S>(mut self, text: S) -> Self
+    where
+        S: Into<Cow<'a, str>>,
+    {
+        self.comments.push(text.into());
+        self
+    }
+
+    /// Replaces header labels starting from the first column.
+    /// Columns without a new label keep the old one.
+    pub fn rename_headers<L, S>(mut self, labels: L) -> Result<Self, CountMismatch>
+    where
+        L: IntoIterator<Item = S>,
+        S: Into<Cow<'a, str>>,
+    {
+        let labels: Vec<_> = labels.into_iter().map(Into::into).collect();
+        let cols_len = self.cols_len();
+        if labels.len() > cols_len {
+            return Err(CountMismatch {
+                expected: cols_len,
+                provided: labels.len(),
+            });
+        }
+
+        let len = labels.len();
+        self.rows.splice(..len, labels);
+        self.update_widths(cols_len);
+        Ok(self)
+    }
+
+    /// Limits the number of rendered data rows, the header is always shown.
+    /// Column widths still account for all the rows,
+    /// so the layout doesn't depend on the limit.
+    pub fn limit(mut self, n: usize) -> Self {
+        self.limit = Some(n);
+        self
+    }
+
+    /// Prepends the `#` column with 1-based data row numbers in text formats.
+    /// The column is added at render time and isn't counted in `cols_len`.
+    pub fn with_row_numbers(mut self, enable: bool) -> Self {
+        self.row_numbers = enable;
+        self
+    }
+
+    /// Sets the alignment of all columns
+    pub fn align(mut self, align: Align) -> Self {
+        self.align = align;
+        self
+    }
+
+    /// Sets alignments of all columns, so the header must be set first.
+    /// Fails if the number of alignments differs from the number of columns.
+    pub fn aligns<A>(mut self, aligns: A) -> Result<Self, CountMismatch>
+    where
+        A: IntoIterator<Item = Align>,
+    {
+        let aligns: Vec<_> = aligns.into_iter().collect();
+        let cols_len = self.cols_len();
+        if aligns.len() != cols_len {
+            return Err(CountMismatch {
+                expected: cols_len,
+                provided: aligns.len(),
+            });
+        }
+
+        self.aligns = aligns;
+        Ok(self)
+    }
+
+    /// Aligns the cell at the row and column unlike its column,
+    /// the row 0 is the header
+    pub fn align_cell(mut self, row: usize, col: usize, align: Align) -> Result<Self, OutOfRange> {
+        if col >= self.cols_len() {
+            return Err(OutOfRange {
+                index: col,
+                len: self.cols_len(),
+            });
+        }
+
+        if row > self.rows_len() {
+            return Err(OutOfRange {
+                index: row,
+                len: self.rows_len() + 1,
+            });
+        }
+
+        self.cell_aligns.insert((row, col), align);
+        Ok(self)
+    }
+
+    /// Aligns numbers of the column on the decimal point in the text format.
+    /// Other cells of the column are aligned to the right.
+    pub fn decimal_align(mut self, col: usize) -> Self {
+        if self.decimal_cols.len() <= col {
+            self.decimal_cols.resize(col + 1, false);
+        }
+
+        self.decimal_cols[col] = true;
+        self
+    }
+
+    /// Sets the decimal separator of numbers like `,` in `1,5`, it's `.` by default.
+    /// Numbers are recognized by it for sorting and decimal alignment.
+    pub fn decimal_separator(mut self, separator: char) -> Self {
+        self.decimal_separator = separator;
+        self
+    }
+
+    /// Skips the separator of thousands like `,` in `1,234` when numbers are recognized.
+    /// A digit is ignored, as is the decimal separator when numbers are recognized.
+    pub fn group_separator(mut self, separator: char) -> Self {
+        self.group_separator = Some(separator).filter(|sep| !sep.is_ascii_digit());
+        self
+    }
+
+    /// Sets the output format
+    pub fn format(mut self, format: Format) -> Self {
+        self.format = format;
+        self
+    }
+
+    /// Sets the border style of the text format
+    pub fn style(mut self, style: Style) -> Self {
+        self.style = style;
+        self.update_widths(self.cols_len());
+        self
+    }
+
+    /// Writes leading and trailing spaces of cells as `&nbsp;` in the Markdown style,
+    /// so the rendered Markdown keeps them
+    pub fn nbsp(mut self, enable: bool) -> Self {
+        self.nbsp = enable;
+        self.update_widths(self.cols_len());
+        self
+    }
+
+    /// Paints header cells of the text format with ANSI escape codes
+    pub fn header_style(mut self, style: Ansi) -> Self {
+        self.header_style = style;
+        self
+    }
+
+    /// Paints data cells of the column in the text format
+    pub fn col_color(mut self, col: usize, color: Color) -> Self {
+        if self.col_colors.len() <= col {
+            self.col_colors.resize(col + 1, None);
+        }
+
+        self.col_colors[col] = Some(color);
+        self
+    }
+
+    /// Renders boolean cells of the column, like `true`, `yes` or `1` and
+    /// `false`, `no` or `0`, as the glyphs in the text format. Other cells are kept.
+    pub fn bool_glyphs<T, F>(mut self, col: usize, true_glyph: T, false_glyph: F) -> Self
+    where
+        T: Into<Cow<'a, str>>,
+        F: Into<Cow<'a, str>>,
+    {
+        if self.bool_glyphs.len() <= col {
+            self.bool_glyphs.resize(col + 1, None);
+        }
+
+        self.bool_glyphs[col] = Some((true_glyph.into(), false_glyph.into()));
+        self.update_widths(self.cols_len());
+        self
+    }
+
+    /// Renders empty data cells as the placeholder like `-` in the text format
+    pub fn empty_placeholder<S>(mut self, placeholder: S) -> Self
+    where
+        S: Into<Cow<'a, str>>,
+    {
+        self.placeholder = Some(placeholder.into());
+        self.update_widths(self.cols_len());
+        self
+    }
+
+    /// Paints the data row with the given index in inverse video in the text format
+    pub fn highlight_row(mut self, index: usize) -> Self {
+        self.highlight = Some(index);
+        self
+    }
+
+    /// Enables the left and right borders of the text format, it's on by default.
+    /// Without them Markdown tables are still valid, like `a | b`.
+    pub fn outer_border(mut self, enable: bool) -> Self {
+        self.outer_border = enable;
+        self
+    }
+
+    /// Omits the padding after cells of the last column in the text format,
+    /// so lines have no trailing spaces before the right border
+    pub fn trim_trailing(mut self, enable: bool) -> Self {
+        self.trim_trailing = enable;
+        self
+    }
+
+    /// Sets the char that fills data cells up to the column width
+    /// on the side opposite to the alignment, it's a space by default
+    pub fn pad_char(mut self, fill: char) -> Self {
+        self.pad_char = fill;
+        self
+    }
+
+    /// Renders short rows added by `tail_ragged` without their missing cells,
+    /// so such lines end early in the text format
+    pub fn ragged(mut self, enable: bool) -> Self {
+        self.ragged = enable;
+        self
+    }
+
+    /// Starts every line of the text format with the prefix,
+    /// like `> ` to put the table in a blockquote
+    pub fn prefix<S>(mut self, prefix: S) -> Self
+    where
+        S: Into<Cow<'a, str>>,
+    {
+        self.prefix = prefix.into();
+        self
+    }
+
+    /// Indents every line of the text format by the number of spaces
+    pub fn indent(self, width: usize) -> Self {
+        self.prefix(" ".repeat(width))
+    }
+
+    /// Sets the number of spaces around cells of the text format, it's 1 by default.
+    pub fn padding(mut self, padding: usize) -> Self {
+        self.padding = padding;
+        self
+    }
+
+    /// Widens columns so each cell of the Markdown separator line has
+    /// at least `min` dashes with colons, it's 3 by default as GitHub requires
+    pub fn min_dashes(mut self, min: usize) -> Self {
+        self.min_dashes = min;
+        self
+    }
+
+    /// Renders a dense table without spaces around cells.
+    pub fn compact(self) -> Self {
+        self.padding(0)
+    }
+
+    /// Expands tabs in cells of the text format to the tab stops of 8 columns
+    pub fn expand_tabs(self, enable: bool) -> Self {
+        self.tab_width(if enable { Some(8) } else { None })
+    }
+
+    /// Sets the distance between tab stops, `None` disables tab expansion
+    pub fn tab_width(mut self, tab_width: Option<usize>) -> Self {
+        self.tab_width = tab_width;
+        self.update_widths(self.cols_len());
+        self
+    }
+
+    /// Limits the width of all columns in the text format,
+    /// longer cells are truncated with an ellipsis
+    pub fn max_width(mut self, width: usize) -> Self {
+        self.max_width = Some(width);
+        self
+    }
+
+    /// Forces widths of all columns in the text format instead of measured ones,
+    /// so tables can share the layout. Longer cells are truncated with an ellipsis.
+    pub fn with_col_widths(mut self, widths: &[usize]) -> Result<Self, CountMismatch> {
+        let cols_len = self.cols_len();
+        if widths.len() != cols_len {
+            return Err(CountMismatch {
+                expected: cols_len,
+                provided: widths.len(),
+            });
+        }
+
+        self.fixed_widths = widths.to_vec();
+        Ok(self)
+    }
+
+    /// Sets the marker of truncated cells, by default it's `…`.
+    /// An empty marker cuts cells without one.
+    pub fn ellipsis<S>(mut self, ellipsis: S) -> Self
+    where
+        S: Into<Cow<'a, str>>,
+    {
+        self.ellipsis = ellipsis.into();
+        self
+    }
+
+    /// Limits the width of the column in the text format,
+    /// overrides the limit set by `max_width`
+    pub fn max_col_width(mut self, col: usize, width: usize) -> Self {
+        if self.max_widths.len() <= col {
+            self.max_widths.resize(col + 1, None);
+        }
+
+        self.max_widths[col] = Some(width);
+        self
+    }
+
+    /// Wraps the text format in a fenced code block,
+    /// so Markdown renderers keep the alignment as is
+    pub fn code_fence(mut self, enable: bool) -> Self {
+        self.code_fence = enable;
+        self
+    }
+
+    /// Ends the output with a line break, it's enabled by default
+    pub fn trailing_newline(mut self, enable: bool) -> Self {
+        self.trailing_newline = enable;
+        self
+    }
+
+    /// Ends lines with `\r\n` instead of `\n`
+    pub fn crlf(mut self, enable: bool) -> Self {
+        self.crlf = enable;
+        self
+    }
+
+    /// Starts the output with the UTF-8 byte order mark
+    pub fn bom(mut self, enable: bool) -> Self {
+        self.bom = enable;
+        self
+    }
+
+    /// Writes the rendered table without building the whole string in memory
+    pub fn write_to<W>(&self, mut out: W) -> io::Result<()>
+    where
+        W: io::Write,
+    {
+        write!(out, "{}", self)
+    }
+
+    /// Moves alignments of single cells along with data rows,
+    /// `order` holds the old index of every kept data row in the new order
+    fn reorder_cell_aligns(&mut self, order: &[usize]) {
+        if self.cell_aligns.is_empty() {
+            return;
+        }
+
+        let mut moved = vec![None; self.rows_len];
+        for (new, &old) in order.iter().enumerate() {
+            moved[old] = Some(new);
+        }
+
+        self.cell_aligns = self
+            .cell_aligns
+            .drain()
+            .filter_map(|((row, col), align)| match row {
+                0 => Some(((0, col), align)),
+                row => moved[row - 1].map(|new| ((new + 1, col), align)),
+            })
+            .collect();
+    }
+
+    /// Recomputes the widths of `cols_len` columns from the cells
+    fn update_widths(&mut self, cols_len: usize) {
+        self.cols_width = vec![0; cols_len];
+        if cols_len == 0 {
+            return;
+        }
+
+        let metrics = self.metrics();
+        let mut widths = vec![0; cols_len];
+        for (idx, row) in self.rows.chunks(cols_len).enumerate() {
+            for (col, (width, cell)) in widths.iter_mut().zip(row).enumerate() {
+                let cell = if idx == 0 {
+                    cell
+                } else {
+                    self.shown_cell(col, cell)
+                };
+                *width = metrics.width(cell).max(*width);
+            }
+        }
+
+        // Rows accounted by `measure` aren't stored, so their widths are kept
+        for (width, &measured) in widths.iter_mut().zip(&self.measured_widths) {
+            *width = measured.max(*width);
+        }
+
+        self.cols_width = widths;
+    }
+
+    /// Returns the text of the data cell of the column in the text format
+    fn shown_cell<'c>(&'c self, col: usize, cell: &'c str) -> &'c str {
+        if let (Some(placeholder), true) = (&self.placeholder, cell.is_empty()) {
+            return placeholder;
+        }
+
+        let glyphs = self.bool_glyphs.get(col).and_then(Option::as_ref);
+        match (glyphs, boolean(cell)) {
+            (Some((yes, _)), Some(true)) => yes,
+            (Some((_, no)), Some(false)) => no,
+            _ => cell,
+        }
+    }
+
+    fn metrics(&self) -> Metrics {
+        Metrics {
+            tab_width: self.tab_width,
+            nbsp: self.nbsp && self.style == Style::Markdown,
+        }
+    }
+
+    fn col_align(&self, col: usize) -> Align {
+        if self.is_decimal(col) {
+            return Align::Right;
+        }
+
+        self.aligns.get(col).copied().unwrap_or(self.align)
+    }
+
+    fn cell_align(&self, row: usize, col: usize) -> Align {
+        match self.cell_aligns.get(&(row, col)) {
+            Some(&align) => align,
+            None => self.col_align(col),
+        }
+    }
+
+    fn is_number(&self, cell: &str) -> bool {
+        number(cell, self.decimal_separator, self.group_separator).is_some()
+    }
+
+    fn is_decimal(&self, col: usize) -> bool {
+        self.decimal_cols.get(col).copied().unwrap_or(false)
+    }
+
+    /// The rendered width of the column
+    fn col_width(&self, col: usize) -> usize {
+        self.limit_width(col, self.cols_width[col])
+    }
+
+    /// Applies the max width of the column
+    fn limit_width(&self, col: usize, width: usize) -> usize {
+        match self
+            .max_widths
+            .get(col)
+            .copied()
+            .flatten()
+            .or(self.max_width)
+        {
+            Some(max) => width.min(max),
+            None => width,
+        }
+    }
+
+    /// The number of rendered data rows
+    fn shown(&self) -> usize {
+        match self.limit {
+            Some(limit) => limit.min(self.rows_len()),
+            None => self.rows_len(),
+        }
+    }
+
+    /// Iterates over rendered data rows
+    fn shown_rows(&self) -> impl Iterator<Item = &[Cow<'a, str>]> {
+        self.rows
+            .chunks(self.cols_len().max(1))
+            .skip(1)
+            .take(self.shown())
+    }
+
+    /// Calls the closure with cells of every data row, the header is skipped
+    pub fn for_each_row<F>(&self, mut f: F)
+    where
+        F: FnMut(&[&str]),
+    {
+        let cols_len = self.cols_len();
+        if cols_len == 0 {
+            return;
+        }
+
+        let mut cells = Vec::with_capacity(cols_len);
+        for row in self.rows.chunks(cols_len).skip(1) {
+            cells.clear();
+            cells.extend(row.iter().map(AsRef::as_ref));
+            f(&cells);
+        }
+    }
+
+    /// Returns the cell at the row and column, the row 0 is the header
+    pub fn cell(&self, row: usize, col: usize) -> Option<&str> {
+        if col >= self.cols_len() || row > self.rows_len() {
+            return None;
+        }
+
+        self.rows
+            .get(row * self.cols_len() + col)
+            .map(AsRef::as_ref)
+    }
+
+    /// Returns true if the table has no header columns.
+    /// An empty table renders as an empty string in every format.
+    pub fn is_empty(&self) -> bool {
+        self.rows.is_empty()
+    }
+
+    /// Returns the widths of columns measured over the header and all data rows
+    pub fn col_widths(&self) -> &[usize] {
+        &self.cols_width
+    }
+
+    /// Returns the columns with cells wider than the width
+    pub fn wide_cols(&self, width: usize) -> Vec<usize> {
+        (0..self.cols_len())
+            .filter(|&col| self.cols_width[col] > width)
+            .collect()
+    }
+
+    pub fn cols_len(&self) -> usize {
+        self.cols_width.len()
+    }
+
+    pub fn rows_len(&self) -> usize {
+        self.rows_len
+    }
+}
+
+impl Default for Table<'_> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Collects rows into a table, the first row is the header.
+/// Panics if a data row has a different number of cells than the header, like [`Table::tail`].
+impl<'a> FromIterator<Vec<&'a str>> for Table<'a> {
+    fn from_iter<I>(iter: I) -> Self
+    where
+        I: IntoIterator<Item = Vec<&'a str>>,
+    {
+        let mut rows = iter.into_iter();
+        match rows.next() {
+            Some(header) => Self::from_rows(header, rows),
+            None => Self::new(),
+        }
+    }
+}
+
+impl<'a> IntoIterator for &'a Table<'a> {
+    type Item = &'a [Cow<'a, str>];
+    type IntoIter = std::slice::Chunks<'a, Cow<'a, str>>;
+
+    /// Iterates over rows of the table, the header goes first
+    fn into_iter(self) -> Self::IntoIter {
+        self.rows.chunks(self.cols_len().max(1))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Parser;
+
+    #[test]
+    fn to_string() {
+        let table = Table::new()
+            .head(["one", "two", "three"])
+            .tail(["four", "five", "six"])
+            .tail(["seven", "eight", "nine"]);
+
+        assert_eq!(
+            table.to_string(),
+            "\
+            | one   | two   | three |\n\
+            |-------|-------|-------|\n\
+            | four  | five  | six   |\n\
+            | seven | eight | nine  |\n\
+            "
+        );
+    }
+
+    #[test]
+    fn sort_by_col() {
+        let table = Table::new()
+            .head(["name", "n"])
+            .tail(["b", "10"])
+            .tail(["c", "9"])
+            .tail(["a", "100"]);
+
+        let table = table.sort_by_col(1, true).unwrap();
+        assert_eq!(table.rows, ["name", "n", "c", "9", "b", "10", "a", "100"]);
+
+        let table = table.sort_by_col(0, false).unwrap();
+        assert_eq!(table.rows, ["name", "n", "c", "9", "b", "10", "a", "100"]);
+
+        let table = table.sort_by_col(0, true).unwrap();
+        assert_eq!(table.rows, ["name", "n", "a", "100", "b", "10", "c", "9"]);
+
+        let err = table.sort_by_col(2, true).err();
+        assert_eq!(err, Some(OutOfRange { index: 2, len: 2 }));
+    }
+
+    #[test]
+    fn limit() {
+        let table = Table::new()
+            .head(["n"])
+            .tail(["1"])
+            .tail(["2"])
+            .tail(["3"])
+            .tail(["4"])
+            .tail(["long"])
+            .limit(2);
+
+        assert_eq!(
+            table.to_string(),
+            "\
+            | n    |\n\
+            |------|\n\
+            | 1    |\n\
+            | 2    |\n\
+            "
+        );
+    }
+
+    #[test]
+    fn row_numbers() {
+        let mut table = Table::new().head(["cell"]);
+        for _ in 0..10 {
+            table = table.tail(["x"]);
+        }
+
+        let table = table.with_row_numbers(true);
+        let rendered = table.to_string();
+        let lines: Vec<_> = rendered.lines().collect();
+        assert_eq!(lines[0], "| #  | cell |");
+        assert_eq!(lines[1], "|----|------|");
+        assert_eq!(lines[2], "|  1 | x    |");
+        assert_eq!(lines[11], "| 10 | x    |");
+    }
+
+    #[test]
+    fn transpose() {
+        let table = Table::new()
+            .head(["key", "a", "b"])
+            .tail(["value", "1", "22"])
+            .transpose();
+
+        assert_eq!(table.cols_len(), 2);
+        assert_eq!(table.rows_len(), 2);
+        assert_eq!(
+            table.to_string(),
+            "\
+            | key | value |\n\
+            |-----|-------|\n\
+            | a   | 1     |\n\
+            | b   | 22    |\n\
+            "
+        );
+
+        let table = Table::new()
+            .head(["key", "a", "b"])
+            .tail(["value", "1", "22"])
+            .aligns([Align::Left, Align::Right, Align::Right])
+            .unwrap()
+            .max_col_width(0, 2)
+            .transpose();
+
+        assert_eq!(table.aligns, []);
+        assert_eq!(table.max_widths, []);
+        assert_eq!(
+            table.to_string(),
+            "\
+            | key | value |\n\
+            |-----|-------|\n\
+            | a   | 1     |\n\
+            | b   | 22    |\n\
+            "
+        );
+    }
+
+    #[test]
+    fn drop_empty_columns() {
+        let table = Table::new()
+            .head(["a", "", "c"])
+            .tail(["1", "", "3"])
+            .tail(["long", "", ""])
+            .drop_empty_columns();
+
+        assert_eq!(table.cols_len(), 2);
+        assert_eq!(
+            table.to_string(),
+            "\
+            | a    | c |\n\
+            |------|---|\n\
+            | 1    | 3 |\n\
+            | long |   |\n\
+            "
+        );
+    }
+
+    #[test]
+    fn select() {
+        let table = Table::new()
+            .head(["a", "b", "c"])
+            .tail(["1", "22", "333"])
+            .select(&[2, 0, 1])
+            .unwrap();
+
+        assert_eq!(
+            table.to_string(),
+            "\
+            | c   | a | b  |\n\
+            |-----|---|----|\n\
+            | 333 | 1 | 22 |\n\
+            "
+        );
+
+        let err = table.select(&[0, 3]).err();
+        assert_eq!(err, Some(OutOfRange { index: 3, len: 3 }));
+    }
+
+    #[test]
+    fn sort_columns() {
+        let table = Table::new()
+            .head(["name", "age", "city"])
+            .tail(["Ann", "31", "Oslo"])
+            .tail(["Bob", "4", "Rome"])
+            .sort_columns();
+
+        assert_eq!(
+            table.to_string(),
+            "\
+            | age | city | name |\n\
+            |-----|------|------|\n\
+            | 31  | Oslo | Ann  |\n\
+            | 4   | Rome | Bob  |\n\
+            "
+        );
+
+        let table = Table::new()
+            .head(["b", "a"])
+            .tail(["1", "22"])
+            .aligns([Align::Right, Align::Left])
+            .unwrap()
+            .max_col_width(1, 1)
+            .sort_columns();
+
+        assert_eq!(
+            table.to_string(),
+            "\
+            | a | b |\n\
+            |---|--:|\n\
+            | … | 1 |\n\
+            "
+        );
+    }
+
+    #[test]
+    fn rename_headers() {
+        let table = Table::new()
+            .head(["a", "b"])
+            .tail(["1", "2"])
+            .rename_headers(["long"])
+            .unwrap();
+
+        assert_eq!(
+            table.to_string(),
+            "\
+            | long | b |\n\
+            |------|---|\n\
+            | 1    | 2 |\n\
+            "
+        );
+
+        let err = table.rename_headers(["x", "y", "z"]).err();
+        assert_eq!(
+            err,
+            Some(CountMismatch {
+                expected: 2,
+                provided: 3,
+            })
+        );
+    }
+
+    #[test]
+    fn formats() {
+        let table = Table::new()
+            .head(["name", "note"])
+            .tail(["a", "x < y"])
+            .tail(["b", "say \"hi\", bye"]);
+
+        let table = table.style(Style::Plain);
+        assert_eq!(
+            table.to_string(),
+            "\
+            name  note\n\
+            a     x < y\n\
+            b     say \"hi\", bye\n\
+            "
+        );
+
+        let table = table.style(Style::Unicode);
+        assert_eq!(
+            table.to_string(),
+            "\
+            ┌──────┬───────────────┐\n\
+            │ name │ note          │\n\
+            ├──────┼───────────────┤\n\
+            │ a    │ x < y         │\n\
+            │ b    │ say \"hi\", bye │\n\
+            └──────┴───────────────┘\n\
+            "
+        );
+
+        let table = table.style(Style::Rounded);
+        assert_eq!(
+            table.to_string(),
+            "\
+            ╭──────┬───────────────╮\n\
+            │ name │ note          │\n\
+            ├──────┼───────────────┤\n\
+            │ a    │ x < y         │\n\
+            │ b    │ say \"hi\", bye │\n\
+            ╰──────┴───────────────╯\n\
+            "
+        );
+
+        let table = table.format(Format::Html);
+        assert_eq!(
+            table.to_string(),
+            "\
+            <table>\n  \
+              <thead>\n    \
+                <tr><th>name</th><th>note</th></tr>\n  \
+              </thead>\n  \
+              <tbody>\n    \
+                <tr><td>a</td><td>x &lt; y</td></tr>\n    \
+                <tr><td>b</td><td>say &quot;hi&quot;, bye</td></tr>\n  \
+              </tbody>\n\
+            </table>\n\
+            "
+        );
+
+        let table = table.format(Format::Csv);
+        assert_eq!(
+            table.to_string(),
+            "name,note\na,x < y\nb,\"say \"\"hi\"\", bye\"\n"
+        );
+
+        let table = table.format(Format::Json);
+        assert_eq!(
+            table.to_string(),
+            "\
+            {\n  \
+              \"header\": [\"name\", \"note\"],\n  \
+              \"rows\": [\n    \
+                [\"a\", \"x < y\"],\n    \
+                [\"b\", \"say \\\"hi\\\", bye\"]\n  \
+              ]\n\
+            }\n\
+            "
+        );
+    }
+
+    #[test]
+    fn align() {
+        let table = Table::new()
+            .head(["name", "price", "note"])
+            .tail(["apple", "1.5", "ok"])
+            .tail(["kiwi", "100.25", "-"])
+            .aligns([Align::Left, Align::Right, Align::Center])
+            .unwrap();
+
+        assert_eq!(
+            table.to_string(),
+            "\
+            | name  |  price | note |\n\
+            |-------|-------:|:----:|\n\
+            | apple |    1.5 |  ok  |\n\
+            | kiwi  | 100.25 |  -   |\n\
+            "
+        );
+    }
+
+    #[test]
+    fn aligns_count() {
+        let res = Table::new()
+            .head(["a", "b"])
+            .aligns([Align::Left, Align::Right, Align::Center]);
+
+        assert_eq!(
+            res.err(),
+            Some(CountMismatch {
+                expected: 2,
+                provided: 3,
+            })
+        );
+
+        let res = Table::new().head(["a", "b"]).aligns([Align::Right]);
+        assert_eq!(
+            res.err(),
+            Some(CountMismatch {
+                expected: 2,
+                provided: 1,
+            })
+        );
+    }
+
+    #[test]
+    fn max_width() {
+        let table = Table::new()
+            .head(["name", "description"])
+            .tail(["something", "very long text"])
+            .max_width(6)
+            .max_col_width(1, 8);
+
+        assert_eq!(
+            table.to_string(),
+            "\
+            | name   | descrip… |\n\
+            |--------|----------|\n\
+            | somet… | very lo… |\n\
+            "
+        );
+    }
+
+    #[test]
+    fn outer_border() {
+        let table = Table::new()
+            .head(["one", "two", "three"])
+            .tail(["four", "five", "6"])
+            .aligns([Align::Left, Align::Center, Align::Right])
+            .unwrap();
+
+        assert_eq!(
+            table.to_string(),
+            "\
+            | one  | two  | three |\n\
+            |------|:----:|------:|\n\
+            | four | five |     6 |\n\
+            "
+        );
+
+        let table = table.outer_border(false);
+        assert_eq!(
+            table.to_string(),
+            "\
+            one  | two  | three\n\
+            -----|:----:|-----:\n\
+            four | five |     6\n\
+            "
+        );
+    }
+
+    #[test]
+    fn trim_trailing() {
+        let table = Table::new()
+            .head(["name", "note"])
+            .tail(["apple", "a long note"])
+            .tail(["kiwi", "ok"])
+            .trim_trailing(true);
+
+        assert_eq!(
+            table.to_string(),
+            "\
+            | name  | note|\n\
+            |-------|-------------|\n\
+            | apple | a long note|\n\
+            | kiwi  | ok|\n\
+            "
+        );
+    }
+
+    #[test]
+    fn compact() {
+        let table = Table::new()
+            .head(["one", "two", "three"])
+            .tail(["four", "five", "6"])
+            .aligns([Align::Left, Align::Center, Align::Right])
+            .unwrap()
+            .compact();
+
+        assert_eq!(
+            table.to_string(),
+            "\
+            |one |two |three|\n\
+            |----|:--:|----:|\n\
+            |four|five|    6|\n\
+            "
+        );
+
+        let table = table.style(Style::Unicode).padding(2);
+        assert_eq!(
+            table.to_string(),
+            "\
+            ┌────────┬────────┬─────────┐\n\
+            │  one   │  two   │  three  │\n\
+            ├────────┼────────┼─────────┤\n\
+            │  four  │  five  │      6  │\n\
+            └────────┴────────┴─────────┘\n\
+            "
+        );
+    }
+
+    #[test]
+    fn escape_html() {
+        assert!(matches!(
+            super::escape_html("plain text"),
+            Cow::Borrowed("plain text")
+        ));
+        assert_eq!(
+            super::escape_html(r#"<a href="x">&</a>"#),
+            "&lt;a href=&quot;x&quot;&gt;&amp;&lt;/a&gt;"
+        );
+    }
+
+    #[test]
+    fn lines() {
+        let table = Table::new()
+            .head(["one", "two"])
+            .tail(["three", "four"])
+            .tail(["5", "6"])
+            .with_row_numbers(true);
+
+        for style in [Style::Markdown, Style::Unicode, Style::Plain] {
+            let table = Table::new()
+                .head(["one", "two"])
+                .tail(["three", "four"])
+                .style(style);
+
+            let lines: Vec<_> = table.lines().collect();
+            assert_eq!(lines, table.to_string().lines().collect::<Vec<_>>());
+        }
+
+        let lines: Vec<_> = table.lines().collect();
+        assert_eq!(
+            lines,
+            [
+                "| # | one   | two  |",
+                "|---|-------|------|",
+                "| 1 | three | four |",
+                "| 2 | 5     | 6    |",
+            ]
+        );
+
+        let table = table.format(Format::Csv);
+        assert_eq!(
+            table.lines().collect::<Vec<_>>(),
+            ["one,two", "three,four", "5,6"]
+        );
+        assert_eq!(Table::new().lines().count(), 0);
+
+        let table = Table::new().head(["a"]).tail(["1"]).code_fence(true);
+        let lines: Vec<_> = table.lines().collect();
+        assert_eq!(lines, table.to_string().lines().collect::<Vec<_>>());
+        assert_eq!(lines, ["```", "| a |", "|---|", "| 1 |", "```"]);
+    }
+
+    #[test]
+    fn cell() {
+        let table = Table::new()
+            .head(["one", "two"])
+            .tail(["three", "four"])
+            .tail(["5", "6"]);
+
+        assert_eq!(table.cell(0, 1), Some("two"));
+        assert_eq!(table.cell(1, 0), Some("three"));
+        assert_eq!(table.cell(2, 1), Some("6"));
+        assert_eq!(table.cell(3, 0), None);
+        assert_eq!(table.cell(1, 2), None);
+        assert_eq!(Table::new().cell(0, 0), None);
+    }
+
+    #[test]
+    fn into_iter() {
+        let table = Table::new()
+            .head(["one", "two"])
+            .tail(["three", "four"])
+            .tail(["5", "6"]);
+
+        let rows: Vec<Vec<_>> = table.into_iter().map(<[_]>::to_vec).collect();
+        assert_eq!(rows, [["one", "two"], ["three", "four"], ["5", "6"]]);
+        assert_eq!(Table::new().into_iter().count(), 0);
+    }
+
+    #[test]
+    fn expand_tabs() {
+        let table = Table::new()
+            .head(["a\tb", "c"])
+            .tail(["long\tx", "y"])
+            .tab_width(Some(4));
+
+        assert_eq!(
+            table.to_string(),
+            "\
+            | a   b     | c |\n\
+            |-----------|---|\n\
+            | long    x | y |\n\
+            "
+        );
+    }
+
+    #[test]
+    fn decimal_align() {
+        let table = Table::new()
+            .head(["price", "name"])
+            .tail(["1.5", "a"])
+            .tail(["100.25", "b"])
+            .tail(["7", "c"])
+            .tail(["n/a", "d"])
+            .decimal_align(0);
+
+        assert_eq!(
+            table.to_string(),
+            "\
+            |  price | name |\n\
+            |-------:|------|\n\
+            |   1.5  | a    |\n\
+            | 100.25 | b    |\n\
+            |   7    | c    |\n\
+            |    n/a | d    |\n\
+            "
+        );
+    }
+
+    #[test]
+    fn decimal_separator() {
+        let table = Table::new()
+            .head(["price"])
+            .tail(["1,5"])
+            .tail(["100,25"])
+            .tail(["7"])
+            .tail(["2.5"])
+            .decimal_separator(',')
+            .decimal_align(0);
+
+        assert_eq!(
+            table.to_string(),
+            "\
+            |  price |\n\
+            |-------:|\n\
+            |   1,5  |\n\
+            | 100,25 |\n\
+            |   7    |\n\
+            |    2.5 |\n\
+            "
+        );
+
+        let table = Table::new()
+            .head(["price"])
+            .tail(["10,5"])
+            .tail(["9,25"])
+            .decimal_separator(',')
+            .sort_by_col(0, true)
+            .unwrap();
+
+        assert_eq!(table.cell(1, 0), Some("9,25"));
+    }
+
+    #[test]
+    fn group_separator() {
+        let table = Table::new()
+            .head(["amount"])
+            .tail(["1,234"])
+            .tail(["56.5"])
+            .group_separator(',')
+            .decimal_align(0);
+
+        assert_eq!(
+            table.to_string(),
+            "\
+            |  amount |\n\
+            |--------:|\n\
+            | 1,234   |\n\
+            |    56.5 |\n\
+            "
+        );
+
+        let table = table.sort_by_col(0, false).unwrap();
+        assert_eq!(table.cell(1, 0), Some("1,234"));
+
+        let table = Table::new().head(["n"]).group_separator('0');
+        assert_eq!(table.group_separator, None);
+        let table = table.group_separator('.');
+        assert!(table.is_number("1.5"));
+        assert!(!table.is_number("1.5.0"));
+    }
+
+    #[test]
+    fn align_cell() {
+        let table = || {
+            Table::new()
+                .head(["name", "price"])
+                .tail(["kiwi", "1.50"])
+                .tail(["fig", "N/A"])
+                .tail(["apple", "12.00"])
+                .aligns([Align::Left, Align::Right])
+                .unwrap()
+                .align_cell(2, 1, Align::Center)
+                .unwrap()
+        };
+
+        assert_eq!(
+            table().to_string(),
+            "\
+            | name  | price |\n\
+            |-------|------:|\n\
+            | kiwi  |  1.50 |\n\
+            | fig   |  N/A  |\n\
+            | apple | 12.00 |\n\
+            "
+        );
+
+        let err = table().align_cell(4, 0, Align::Right).err();
+        assert_eq!(err, Some(OutOfRange { index: 4, len: 4 }));
+
+        // The alignment moves with its cell
+        assert_eq!(table().transpose().cell_align(1, 2), Align::Center);
+        let table = table()
+            .sort_by_col(1, true)
+            .unwrap()
+            .select(&[1, 0])
+            .unwrap();
+        assert_eq!(
+            table.to_string(),
+            "\
+            | price | name  |\n\
+            |------:|-------|\n\
+            |  1.50 | kiwi  |\n\
+            | 12.00 | apple |\n\
+            |  N/A  | fig   |\n\
+            "
+        );
+    }
+
+    #[test]
+    fn header_style() {
+        let table = Table::new()
+            .head(["one", "two"])
+            .tail(["three", "4"])
+            .header_style(Ansi::new().bold());
+
+        assert_eq!(
+            table.to_string(),
+            "\
+            | \x1b[1mone\x1b[0m   | \x1b[1mtwo\x1b[0m |\n\
+            |-------|-----|\n\
+            | three | 4   |\n\
+            "
+        );
+    }
+
+    #[test]
+    fn col_color() {
+        let table = Table::new()
+            .head(["name", "status"])
+            .tail(["a", "ok"])
+            .tail(["b", "FAIL"])
+            .col_color(1, Color::Red);
+
+        assert_eq!(
+            table.to_string(),
+            "\
+            | name | status |\n\
+            |------|--------|\n\
+            | a    | \x1b[31mok\x1b[0m     |\n\
+            | b    | \x1b[31mFAIL\x1b[0m   |\n\
+            "
+        );
+    }
+
+    #[test]
+    fn bool_glyphs() {
+        let table = Table::new()
+            .head(["name", "ok"])
+            .tail(["a", "true"])
+            .tail(["b", "false"])
+            .tail(["c", "-"])
+            .aligns([Align::Left, Align::Center])
+            .unwrap()
+            .bool_glyphs(1, "✓", "✗");
+
+        assert_eq!(
+            table.to_string(),
+            "\
+            | name | ok |\n\
+            |------|:--:|\n\
+            | a    | ✓  |\n\
+            | b    | ✗  |\n\
+            | c    | -  |\n\
+            "
+        );
+    }
+
+    #[test]
+    fn empty_placeholder() {
+        let table = Table::new()
+            .head(["name", "x"])
+            .tail(["a", ""])
+            .tail(["", "1"])
+            .empty_placeholder("∅∅");
+
+        assert_eq!(
+            table.to_string(),
+            "\
+            | name | x  |\n\
+            |------|----|\n\
+            | a    | ∅∅ |\n\
+            | ∅∅   | 1  |\n\
+            "
+        );
+        assert_eq!(table.cell(1, 1), Some(""));
+    }
+
+    #[test]
+    fn truncate_by_align() {
+        let table = Table::new()
+            .head(["left", "right", "center"])
+            .tail(["abcdef", "100.025", "abcdefg"])
+            .aligns([Align::Left, Align::Right, Align::Center])
+            .unwrap()
+            .max_width(5);
+
+        assert_eq!(
+            table.to_string(),
+            "\
+            | left  | right | …ent… |\n\
+            |-------|------:|:-----:|\n\
+            | abcd… | ….025 | …cde… |\n\
+            "
+        );
+    }
+
+    #[test]
+    fn ellipsis() {
+        let table = || {
+            Table::new()
+                .head(["left", "right", "center"])
+                .tail(["abcdefgh", "10000.025", "abcdefghi"])
+                .aligns([Align::Left, Align::Right, Align::Center])
+                .unwrap()
+                .max_width(7)
+        };
+
+        assert_eq!(
+            table().ellipsis("...").to_string(),
+            "\
+            | left    |   right | center  |\n\
+            |---------|--------:|:-------:|\n\
+            | abcd... | ....025 | ...e... |\n\
+            "
+        );
+
+        assert_eq!(
+            table().ellipsis("").to_string(),
+            "\
+            | left    |   right | center  |\n\
+            |---------|--------:|:-------:|\n\
+            | abcdefg | 000.025 | bcdefgh |\n\
+            "
+        );
+    }
+
+    #[test]
+    fn dedup() {
+        let table = Table::new()
+            .head(["a"])
+            .tail(["1"])
+            .tail(["1"])
+            .tail(["2"])
+            .tail(["1"]);
+
+        let rows = |table: &Table| {
+            table
+                .into_iter()
+                .flatten()
+                .map(|cell| cell.to_string())
+                .collect::<Vec<_>>()
+        };
+
+        let table = table.dedup();
+        assert_eq!(rows(&table), ["a", "1", "2", "1"]);
+        assert_eq!(table.rows_len(), 3);
+
+        let table = table.dedup_all();
+        assert_eq!(rows(&table), ["a", "1", "2"]);
+        assert_eq!(table.rows_len(), 2);
+    }
+
+    #[test]
+    fn stream() {
+        let rows = [["three", "4"], ["5", "six"]];
+        let table = || {
+            Table::new()
+                .head(["one", "two"])
+                .style(Style::Unicode)
+                .with_row_numbers(true)
+        };
+
+        let stored = rows.iter().fold(table(), |table, row| table.tail(*row));
+        let measured = rows.iter().fold(table(), |table, row| table.measure(row));
+
+        let mut out = vec![];
+        let mut stream = measured.stream();
+        stream.head(&mut out).unwrap();
+        for row in &rows {
+            stream.row(&mut out, row.iter().copied()).unwrap();
+        }
+        stream.finish(&mut out).unwrap();
+
+        assert_eq!(String::from_utf8(out).unwrap(), stored.to_string());
+    }
+
+    #[test]
+    fn stream_line_endings() {
+        let rows = [["1", "multi\nline"], ["2", "x"]];
+        let table = |trailing| {
+            Table::new()
+                .head(["one", "two"])
+                .crlf(true)
+                .bom(true)
+                .trailing_newline(trailing)
+        };
+
+        for trailing in [true, false] {
+            let stored = rows
+                .iter()
+                .fold(table(trailing), |table, row| table.tail(*row));
+            let measured = rows
+                .iter()
+                .fold(table(trailing), |table, row| table.measure(row));
+
+            let mut out = vec![];
+            let mut stream = measured.stream();
+            stream.head(&mut out).unwrap();
+            for row in &rows {
+                stream.row(&mut out, row.iter().copied()).unwrap();
+            }
+            stream.finish(&mut out).unwrap();
+
+            let out = String::from_utf8(out).unwrap();
+            assert!(out.starts_with('\u{feff}'));
+            assert_eq!(out.matches("\r\n").count(), out.matches('\n').count());
+            assert_eq!(out, stored.to_string());
+        }
+    }
+
+    #[test]
+    fn measure_restyle() {
+        let table = Table::new()
+            .head(["a"])
+            .measure(["xxxxxxxx"])
+            .style(Style::Unicode)
+            .nbsp(true);
+
+        assert_eq!(table.col_widths(), [8]);
+
+        let table = Table::new()
+            .head(["a"])
+            .measure(["yes"])
+            .bool_glyphs(0, "✓", "✗");
+
+        assert_eq!(table.col_widths(), [3]);
+
+        let table = Table::new()
+            .head(["a"])
+            .measure(["long"])
+            .empty_placeholder("-");
+
+        assert_eq!(table.col_widths(), [4]);
+    }
+
+    #[test]
+    fn writer() {
+        let table = Table::new()
+            .head(["name", "qty"])
+            .with_col_widths(&[6, 3])
+            .unwrap()
+            .style(Style::Unicode);
+
+        let mut writer = table.writer(vec![]).unwrap();
+        let head = "\
+            ┌────────┬─────┐\n\
+            │ name   │ qty │\n\
+            ├────────┼─────┤\n\
+            ";
+        assert_eq!(writer.get_ref(), head.as_bytes());
+
+        writer.row(["kiwi", "3"]).unwrap();
+        let row = "│ kiwi   │ 3   │\n";
+        assert_eq!(writer.get_ref(), format!("{}{}", head, row).as_bytes());
+
+        writer.row(["pineapple", "120"]).unwrap();
+        let out = String::from_utf8(writer.finish().unwrap()).unwrap();
+        assert_eq!(
+            out,
+            "\
+            ┌────────┬─────┐\n\
+            │ name   │ qty │\n\
+            ├────────┼─────┤\n\
+            │ kiwi   │ 3   │\n\
+            │ pinea… │ 120 │\n\
+            └────────┴─────┘\n\
+            "
+        );
+    }
+
+    #[test]
+    fn render_header_only() {
+        let table = Table::new()
+            .head(["name", "qty", "note"])
+            .tail(["pineapple", "3", "-"])
+            .aligns([Align::Left, Align::Right, Align::Left])
+            .unwrap();
+
+        assert_eq!(
+            table.render_header_only(),
+            "\
+            | name      | qty | note |\n\
+            |-----------|----:|------|\n\
+            "
+        );
+
+        let table = table.style(Style::Unicode).padding(0);
+        assert_eq!(table.render_header_only().lines().count(), 2);
+    }
+
+    #[test]
+    fn jira() {
+        let table = Table::new()
+            .head(["h1", "h2"])
+            .tail(["a", "b"])
+            .tail(["c|d", ""])
+            .format(Format::Jira);
+
+        assert_eq!(table.to_string(), "||h1||h2||\n|a|b|\n|c\\|d| |\n");
+    }
+
+    #[test]
+    fn from_iter() {
+        let rows = vec![vec!["one", "two"], vec!["three", "4"]];
+        let table: Table = rows.into_iter().collect();
+        assert_eq!(
+            table.to_string(),
+            "\
+            | one   | two |\n\
+            |-------|-----|\n\
+            | three | 4   |\n\
+            "
+        );
+    }
+
+    #[test]
+    fn group_digits() {
+        let table = Table::new()
+            .head(["n"])
+            .tail(["1000000"])
+            .tail(["-12345"])
+            .tail(["999"])
+            .tail(["1234.5"])
+            .tail(["abc"])
+            .group_digits(0, ',')
+            .unwrap();
+
+        let cells: Vec<_> = table.into_iter().flatten().collect();
+        assert_eq!(cells, ["n", "1,000,000", "-12,345", "999", "1234.5", "abc"]);
+        assert_eq!(table.cols_width, [9]);
+        assert!(Table::new().head(["n"]).group_digits(1, ',').is_err());
+    }
+
+    #[test]
+    fn rendered_width() {
+        let table = Table::new()
+            .head(["one", "two"])
+            .tail(["three", "4"])
+            .with_row_numbers(true);
+
+        for style in [Style::Markdown, Style::Unicode, Style::Plain] {
+            let table = Table::new()
+                .head(["one", "two"])
+                .tail(["three", "4"])
+                .style(style)
+                .padding(2);
+
+            let line = table.lines().next().unwrap();
+            assert_eq!(table.rendered_width(), line.chars().count());
+        }
+
+        let line = table.lines().next().unwrap();
+        assert_eq!(table.rendered_width(), line.chars().count());
+        let table = table.compact();
+        assert_eq!(table.rendered_width(), 15);
+        assert_eq!(table.min_dashes(0).rendered_width(), 13);
+        assert_eq!(Table::new().rendered_width(), 0);
+    }
+
+    #[test]
+    fn min_dashes() {
+        let table = Table::new().head(["a", "b"]).tail(["1", "2"]).compact();
+        assert_eq!(
+            table.to_string(),
+            "\
+            |a  |b  |\n\
+            |---|---|\n\
+            |1  |2  |\n\
+            "
+        );
+
+        let table = table.padding(1).outer_border(false).min_dashes(4);
+        assert_eq!(
+            table.to_string(),
+            "\
+            a   | b\n\
+            ----|----\n\
+            1   | 2\n\
+            "
+        );
+    }
+
+    #[test]
+    fn highlight_row() {
+        let table = Table::new()
+            .head(["one", "two"])
+            .tail(["a", "b"])
+            .tail(["c", "d"]);
+
+        let plain: Vec<_> = table.lines().collect();
+        let table = table.highlight_row(1);
+        let highlighted: Vec<_> = table.lines().collect();
+
+        assert_eq!(highlighted[..3], plain[..3]);
+        assert_eq!(highlighted[3], "| \x1b[7mc\x1b[0m   | \x1b[7md\x1b[0m   |");
+    }
+
+    #[test]
+    fn header_from_str() {
+        let input = "\"apple\" \"1.5\" \"ok\"\n\"kiwi\" \"100.25\" \"-\"\n";
+        let lexemes = Parser::new(input).map(Result::unwrap);
+        let table = Table::new()
+            .header_from_str("name, price, note", ',')
+            .tail_lexemes(lexemes);
+
+        assert_eq!(table.rows_len(), 2);
+        assert_eq!(
+            table.to_string(),
+            "\
+            | name  | price  | note |\n\
+            |-------|--------|------|\n\
+            | apple | 1.5    | ok   |\n\
+            | kiwi  | 100.25 | -    |\n\
+            "
+        );
+    }
+
+    #[test]
+    fn from_lexemes() {
+        let lexemes = [
+            Lexeme::Cell("a".into()),
+            Lexeme::Cell("b".into()),
+            Lexeme::Cell("c".into()),
+            Lexeme::NewLine,
+            Lexeme::Cell("0".into()),
+            Lexeme::Cell("1".into()),
+            Lexeme::NewLine,
+            Lexeme::Cell("2".into()),
+            Lexeme::Cell("3".into()),
+            Lexeme::Cell("4".into()),
+            Lexeme::Cell("5".into()),
+            Lexeme::NewLine,
+            Lexeme::NewLine,
+        ];
+
+        let table = Table::from_lexemes(lexemes);
+        assert_eq!(table.rows_len(), 3);
+        assert_eq!(
+            table.to_string(),
+            "\
+            | a | b | c |\n\
+            |---|---|---|\n\
+            | 0 | 1 |   |\n\
+            | 2 | 3 | 4 |\n\
+            |   |   |   |\n\
+            "
+        );
+
+        assert!(Table::from_lexemes([Lexeme::NewLine, Lexeme::NewLine]).is_empty());
+    }
+
+    #[test]
+    fn split_lexemes() {
+        let input = "\n\"a\" \"b\"\n\"1\" \"2\"\n\n\n\"name\"\n\"kiwi\"\n\"apple\"\n";
+        let lexemes = Parser::new(input).map(Result::unwrap);
+        let tables: Vec<_> = Table::split_lexemes(lexemes)
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+
+        assert_eq!(
+            tables,
+            [
+                "\
+                | a | b |\n\
+                |---|---|\n\
+                | 1 | 2 |\n\
+                ",
+                "\
+                | name  |\n\
+                |-------|\n\
+                | kiwi  |\n\
+                | apple |\n\
+                ",
+            ]
+        );
+    }
+
+    #[test]
+    fn to_inline() {
+        let table = Table::new().head(["a", "b"]).tail(["1", "2"]);
+        assert_eq!(table.to_inline(), "a=1, b=2");
+
+        let table = table.tail(["3", "4"]);
+        assert_eq!(table.to_inline(), table.to_string());
+
+        let table = table.limit(1);
+        assert_eq!(table.to_inline(), "a=1, b=2");
+
+        let table = table.limit(0);
+        assert_eq!(table.to_inline(), table.to_string());
+    }
+
+    #[test]
+    fn for_each_row() {
+        let table = Table::new()
+            .head(["name", "qty"])
+            .tail(["kiwi", "3"])
+            .tail(["apple", "12"]);
+
+        let mut total = 0;
+        table.for_each_row(|row| total += row[1].parse::<i32>().unwrap());
+        assert_eq!(total, 15);
+    }
+
+    #[test]
+    fn col_widths() {
+        let table = Table::new()
+            .head(["name", "price"])
+            .tail(["apple", "1.5"])
+            .tail(["kiwi", "100.25"]);
+
+        assert_eq!(table.col_widths(), [5, 6]);
+    }
+
+    #[test]
+    fn wide_cols() {
+        let table =
+            Table::new()
+                .head(["id", "note", "name"])
+                .tail(["1", "a very long note", "kiwi"]);
+
+        assert_eq!(table.wide_cols(5), [1]);
+        assert_eq!(table.wide_cols(3), [1, 2]);
+        assert!(table.wide_cols(16).is_empty());
+
+        let table = table.max_width(5);
+        assert_eq!(
+            table.to_string(),
+            "\
+            | id | note  | name |\n\
+            |----|-------|------|\n\
+            | 1  | a ve… | kiwi |\n\
+            "
+        );
+    }
+
+    #[test]
+    fn with_col_widths() {
+        let first = Table::new().head(["name", "price"]).tail(["apple", "1.5"]);
+
+        let second = Table::new()
+            .head(["name", "price"])
+            .tail(["pineapple", "100.25"]);
+
+        let widths = [6, 5];
+        let first = first.with_col_widths(&widths).unwrap();
+        let second = second.with_col_widths(&widths).unwrap();
+
+        assert_eq!(
+            first.to_string(),
+            "\
+            | name   | price |\n\
+            |--------|-------|\n\
+            | apple  | 1.5   |\n\
+            "
+        );
+
+        assert_eq!(
+            second.to_string(),
+            "\
+            | name   | price |\n\
+            |--------|-------|\n\
+            | pinea… | 100.… |\n\
+            "
+        );
+
+        assert!(Table::new().head(["a"]).with_col_widths(&[1, 2]).is_err());
+    }
+
+    #[test]
+    fn code_fence() {
+        let table = Table::new()
+            .head(["a", "b"])
+            .tail(["1", "22"])
+            .code_fence(true);
+
+        assert_eq!(
+            table.to_string(),
+            "\
+            ```\n\
+            | a | b  |\n\
+            |---|----|\n\
+            | 1 | 22 |\n\
+            ```\n\
+            "
+        );
+
+        let fenced = Table::new().head(["a"]).tail(["```"]).code_fence(true);
+        assert_eq!(
+            fenced.to_string(),
+            "\
+            ````\n\
+            | a   |\n\
+            |-----|\n\
+            | ``` |\n\
+            ````\n\
+            "
+        );
+
+        let table = table.format(Format::Csv);
+        assert_eq!(table.to_string(), "a,b\n1,22\n");
+    }
+
+    #[test]
+    fn crlf_bom() {
+        let table = Table::new()
+            .head(["a", "b"])
+            .tail(["1", "22"])
+            .crlf(true)
+            .bom(true);
+
+        assert_eq!(
+            table.to_string(),
+            "\u{feff}| a | b  |\r\n|---|----|\r\n| 1 | 22 |\r\n"
+        );
+
+        let table = table.trailing_newline(false).format(Format::Csv);
+        assert_eq!(table.to_string(), "\u{feff}a,b\r\n1,22");
+        assert_eq!(table.lines().collect::<Vec<_>>(), ["a,b", "1,22"]);
+    }
+
+    #[test]
+    fn trailing_newline() {
+        let table = || Table::new().head(["a", "b"]).tail(["0", "1"]);
+        assert_eq!(table().to_string(), "| a | b |\n|---|---|\n| 0 | 1 |\n");
+        assert_eq!(
+            table().trailing_newline(false).to_string(),
+            "| a | b |\n|---|---|\n| 0 | 1 |"
+        );
+
+        let csv = table().format(Format::Csv).trailing_newline(false);
+        assert_eq!(csv.to_string(), "a,b\n0,1");
+    }
+
+    #[test]
+    fn pad_char() {
+        let table = Table::new()
+            .head(["chapter", "page"])
+            .tail(["Intro", "1"])
+            .tail(["Usage", "12"])
+            .aligns([Align::Left, Align::Right])
+            .unwrap()
+            .pad_char('.');
+
+        assert_eq!(
+            table.to_string(),
+            "\
+            | chapter | page |\n\
+            |---------|-----:|\n\
+            | Intro.. | ...1 |\n\
+            | Usage.. | ..12 |\n\
+            "
+        );
+    }
+
+    #[test]
+    fn ragged() {
+        let table = Table::new()
+            .head(["a", "b", "c"])
+            .tail_ragged(["0"])
+            .tail(["1", "2", "3"])
+            .sort_by_col(0, false)
+            .unwrap();
+
+        assert_eq!(
+            table.to_string(),
+            "| a | b | c |\n|---|---|---|\n| 1 | 2 | 3 |\n| 0 |   |   |\n"
+        );
+
+        assert_eq!(
+            table.ragged(true).to_string(),
+            "| a | b | c |\n|---|---|---|\n| 1 | 2 | 3 |\n| 0 |\n"
+        );
+    }
+
+    #[test]
+    fn empty() {
+        let table = Table::new();
+        assert!(table.is_empty());
+        assert_eq!(table.to_string(), "");
+        assert!(!Table::new().head(["a"]).is_empty());
+    }
+
+    #[test]
+    fn indent() {
+        let table = Table::new()
+            .head(["a", "b"])
+            .tail(["0", "1"])
+            .tail(["2", "3"])
+            .indent(4);
+
+        assert_eq!(
+            table.to_string(),
+            "    | a | b |\n    |---|---|\n    | 0 | 1 |\n    | 2 | 3 |\n"
+        );
+        assert_eq!(table.rendered_width(), 13);
+    }
+
+    #[test]
+    fn append() {
+        let first = Table::new()
+            .head(["name", "qty", "note"])
+            .tail(["kiwi", "3", "-"]);
+
+        let second = Table::new()
+            .head(["x", "y", "z"])
+            .tail(["apple", "120", "ok"]);
+
+        let table = first.append(second).unwrap();
+        assert_eq!(table.rows_len(), 2);
+        assert_eq!(table.col_widths(), [5, 3, 4]);
+        assert_eq!(
+            table.to_string(),
+            "\
+            | name  | qty | note |\n\
+            |-------|-----|------|\n\
+            | kiwi  | 3   | -    |\n\
+            | apple | 120 | ok   |\n\
+            "
+        );
+
+        let err = table.append(Table::new().head(["a"])).err();
+        assert_eq!(
+            err,
+            Some(CountMismatch {
+                expected: 3,
+                provided: 1
+            })
+        );
+    }
+
+    #[test]
+    fn tail_lenient() {
+        let table = Table::new()
+            .head(["a", "b", "c"])
+            .tail_lenient(["1"])
+            .tail_lenient(["2", "3", "4", "wide cell"]);
+
+        assert_eq!(table.rows_len(), 2);
+        assert_eq!(table.col_widths(), [1, 1, 1]);
+        assert_eq!(
+            table.to_string(),
+            "\
+            | a | b | c |\n\
+            |---|---|---|\n\
+            | 1 |   |   |\n\
+            | 2 | 3 | 4 |\n\
+            "
+        );
+    }
+
+    #[test]
+    fn try_tail() {
+        let table = Table::new().head(["a", "b"]).try_tail(["0", "1"]).unwrap();
+        assert_eq!(
+            table.try_tail(["2"]).err(),
+            Some(RowLenError {
+                row: 1,
+                expected: 2,
+                provided: 1,
+                cells: vec!["2".to_owned()],
+            })
+        );
+    }
+
+    #[test]
+    fn extend_rows() {
+        let table = Table::new()
+            .head(["a", "b"])
+            .extend_rows(vec![vec!["0", "1"], vec!["2", "3"]])
+            .unwrap();
+
+        assert_eq!(table.rows_len(), 2);
+
+        let err = table
+            .extend_rows(vec![vec!["4", "5"], vec!["6 7"]])
+            .err()
+            .unwrap();
+
+        assert_eq!(
+            err,
+            RowLenError {
+                row: 3,
+                expected: 2,
+                provided: 1,
+                cells: vec!["6 7".to_owned()],
+            }
+        );
+        assert_eq!(err.to_string(), "row 3 has 1 cells, expected 2: [\"6 7\"]");
+    }
+
+    #[test]
+    #[should_panic(expected = "row 1 has 3 cells, expected 2: [\"x\", \"y\", \"z\"]")]
+    fn tail_len() {
+        let _ = Table::new()
+            .head(["a", "b"])
+            .tail(["0", "1"])
+            .tail(["x", "y", "z"]);
+    }
+
+    #[test]
+    fn nbsp() {
+        let table = Table::new().head(["a", "b"]).tail(["  x ", "y"]).nbsp(true);
+        assert_eq!(
+            table.to_string(),
+            "\
+            | a                   | b |\n\
+            |---------------------|---|\n\
+            | &nbsp;&nbsp;x&nbsp; | y |\n\
+            "
+        );
+
+        let table = table.style(Style::Plain);
+        assert_eq!(table.to_string(), "a     b\n  x   y\n");
+    }
+
+    #[test]
+    fn zero_columns() {
+        let table = Table::new()
+            .head(Vec::<&str>::new())
+            .tail(Vec::<&str>::new())
+            .tail(Vec::<&str>::new());
+
+        assert_eq!(table.cols_len(), 0);
+        assert_eq!(table.rows_len(), 0);
+        assert!(table.is_empty());
+        assert_eq!(table.to_string(), "");
+        assert_eq!(table.format(Format::Json).to_string(), "");
+    }
+
+    #[test]
+    fn comment() {
+        let table = Table::new()
+            .head(["a"])
+            .tail(["0"])
+            .style(Style::Markdown)
+            .comment(" generated");
+
+        assert_eq!(table.to_string(), "# generated\n| a |\n|---|\n| 0 |\n");
+    }
+
+    #[test]
+    fn keep_comments() {
+        let parser = Parser::new("# generated\n\"a\"\n\"0\"\n").comments(true);
+        let table = Table::from_lexemes(parser.map(Result::unwrap));
+        assert_eq!(table.to_string(), "# generated\n| a |\n|---|\n| 0 |\n");
+
+        let parser = Parser::new("\"1\"\n#added\n").comments(true);
+        let table = table.tail_lexemes(parser.map(Result::unwrap));
+        assert_eq!(
+            table.to_string(),
+            "# generated\n#added\n| a |\n|---|\n| 0 |\n| 1 |\n"
         );
     }
 }
@@ -0,0 +1,68 @@
+use super::Table;
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+use std::borrow::Cow;
+
+/// The logical content of the table
+#[derive(Serialize)]
+struct DataRef<'t, 'a> {
+    header: &'t [Cow<'a, str>],
+    rows: Vec<&'t [Cow<'a, str>]>,
+}
+
+#[derive(Deserialize)]
+struct Data<'a> {
+    #[serde(borrow)]
+    header: Vec<Cow<'a, str>>,
+    #[serde(borrow)]
+    rows: Vec<Vec<Cow<'a, str>>>,
+}
+
+/// Serializes the header and all data rows as `{"header": [..], "rows": [[..]]}`
+impl Serialize for Table<'_> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let cols_len = self.cols_len();
+        let data = DataRef {
+            header: &self.rows[..cols_len],
+            rows: self.rows.chunks(cols_len.max(1)).skip(1).collect(),
+        };
+
+        data.serialize(serializer)
+    }
+}
+
+impl<'de: 'a, 'a> Deserialize<'de> for Table<'a> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let data = Data::deserialize(deserializer)?;
+        let cols_len = data.header.len();
+        if let Some(row) = data.rows.iter().find(|row| row.len() != cols_len) {
+            return Err(de::Error::invalid_length(
+                row.len(),
+                &format!("{} columns", cols_len).as_str(),
+            ));
+        }
+
+        Ok(Table::from_rows(data.header, data.rows))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let table = Table::from_rows(["a", "b"], [["1", "2"], ["3", "4"]]);
+        let json = serde_json::to_string(&table).unwrap();
+        assert_eq!(json, r#"{"header":["a","b"],"rows":[["1","2"],["3","4"]]}"#);
+
+        let parsed: Table = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed.to_string(), table.to_string());
+        assert!(serde_json::from_str::<Table>(r#"{"header":["a"],"rows":[["1","2"]]}"#).is_err());
+    }
+}
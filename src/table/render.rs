@@ -0,0 +1,1083 @@
+use super::{encode_spaces, escape_html, expand_tabs, Table};
+use crate::{
+    ansi,
+    style::{Borders, Rule},
+    Align, Ansi, Format, Style,
+};
+use std::{borrow::Cow, fmt, io};
+
+/// A line of the text format
+#[derive(Copy, Clone, Eq, PartialEq)]
+enum Line {
+    Comment(usize),
+    Top,
+    Header,
+    Separator,
+    Row(usize),
+    Bottom,
+}
+
+/// The kind of a row of the text format
+#[derive(Copy, Clone, Eq, PartialEq)]
+enum Row {
+    Header,
+    Data,
+    Highlighted,
+}
+
+/// Column widths of the text format
+struct Layout {
+    numbers: bool,
+    widths: Vec<usize>,
+    decimals: Vec<Option<Decimal>>,
+}
+
+impl Layout {
+    /// Returns the width of the table column
+    fn col_width(&self, col: usize) -> usize {
+        self.widths[col + self.numbers as usize]
+    }
+}
+
+/// Widths of integer and fractional parts of numbers in a column
+#[derive(Copy, Clone, Default)]
+struct Decimal {
+    int: usize,
+    frac: usize,
+}
+
+impl Decimal {
+    /// Splits the number before the decimal separator
+    fn split(number: &str, separator: char) -> (&str, &str) {
+        let number = number.trim();
+        number.split_at(number.find(separator).unwrap_or(number.len()))
+    }
+
+    fn fit(self, number: &str, separator: char) -> Self {
+        let (int, frac) = Self::split(number, separator);
+        Self {
+            int: self.int.max(int.chars().count()),
+            frac: self.frac.max(frac.chars().count()),
+        }
+    }
+
+    /// Pads the number so its decimal point is at the same position
+    fn pad(self, number: &str, separator: char) -> String {
+        let (int, frac) = Self::split(number, separator);
+        format!(
+            "{:>int$}{:frac$}",
+            int,
+            frac,
+            int = self.int,
+            frac = self.frac
+        )
+    }
+}
+
+/// Displays the output of the closure
+struct Fmt<F>(F);
+
+impl<F> Fmt<F>
+where
+    F: Fn(&mut fmt::Formatter) -> fmt::Result,
+{
+    fn new(f: F) -> Self {
+        Self(f)
+    }
+}
+
+impl<F> fmt::Display for Fmt<F>
+where
+    F: Fn(&mut fmt::Formatter) -> fmt::Result,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        (self.0)(f)
+    }
+}
+
+/// Holds back a line break until more text follows, so the last one is dropped
+struct TrimNewline<W> {
+    out: W,
+    pending: bool,
+}
+
+impl<W> fmt::Write for TrimNewline<W>
+where
+    W: fmt::Write,
+{
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        if s.is_empty() {
+            return Ok(());
+        }
+
+        if self.pending {
+            self.out.write_str("\n")?;
+        }
+
+        self.pending = s.ends_with('\n');
+        let s = if self.pending { &s[..s.len() - 1] } else { s };
+        self.out.write_str(s)
+    }
+}
+
+/// Replaces line breaks with `\r\n`
+struct Crlf<W>(W);
+
+impl<W> fmt::Write for Crlf<W>
+where
+    W: fmt::Write,
+{
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        let mut lines = s.split('\n');
+        if let Some(line) = lines.next() {
+            self.0.write_str(line)?;
+        }
+
+        for line in lines {
+            self.0.write_str("\r\n")?;
+            self.0.write_str(line)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Writes the text format row by row, so rows don't have to be stored in the table.
+/// Created by [`Table::stream`].
+pub struct Stream<'t, 'a> {
+    table: &'t Table<'a>,
+    layout: Layout,
+    shown: usize,
+    written: usize,
+    /// A line is written
+    started: bool,
+}
+
+impl Stream<'_, '_> {
+    /// Writes the top rule, the header and the separator
+    pub fn head<W>(&mut self, mut out: W) -> io::Result<()>
+    where
+        W: io::Write,
+    {
+        let separator = self.shown != 0;
+        let comments = (0..self.table.comments.len()).map(Line::Comment);
+        for line in comments.chain([Line::Top, Line::Header, Line::Separator]) {
+            if line != Line::Separator || separator {
+                self.write_line(&mut out, line)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Writes the data row, rows over the limit are skipped
+    pub fn row<'r, W, R, S>(&mut self, mut out: W, row: R) -> io::Result<()>
+    where
+        W: io::Write,
+        R: IntoIterator<Item = S>,
+        S: Into<Cow<'r, str>>,
+    {
+        let row: Vec<_> = row.into_iter().map(Into::into).collect();
+        assert_eq!(row.len(), self.table.cols_len());
+        if self.written == self.shown || self.table.is_empty() {
+            return Ok(());
+        }
+
+        let (table, layout, n) = (self.table, &self.layout, self.written);
+        self.written += 1;
+        let text = Fmt::new(|f| table.fmt_row(f, layout, n, &row));
+        table.write_text(&mut out, &mut self.started, text)
+    }
+
+    /// Writes the bottom rule
+    pub fn finish<W>(mut self, mut out: W) -> io::Result<()>
+    where
+        W: io::Write,
+    {
+        self.write_line(&mut out, Line::Bottom)
+    }
+
+    fn write_line<W>(&mut self, out: &mut W, line: Line) -> io::Result<()>
+    where
+        W: io::Write,
+    {
+        self.table
+            .write_line(out, &mut self.started, &self.layout, line)
+    }
+}
+
+/// Writes the text format to the output as rows come and flushes every row.
+/// Widths are known up front from the header and [`Table::with_col_widths`],
+/// longer cells are truncated. Created by [`Table::writer`].
+pub struct TableWriter<'a, W> {
+    table: Table<'a>,
+    layout: Layout,
+    out: W,
+    written: usize,
+    /// A line is written
+    started: bool,
+}
+
+impl<W> TableWriter<'_, W>
+where
+    W: io::Write,
+{
+    /// Writes and flushes the data row, rows over the limit are skipped
+    pub fn row<'r, R, S>(&mut self, row: R) -> io::Result<()>
+    where
+        R: IntoIterator<Item = S>,
+        S: Into<Cow<'r, str>>,
+    {
+        let row: Vec<_> = row.into_iter().map(Into::into).collect();
+        assert_eq!(row.len(), self.table.cols_len());
+        if Some(self.written) == self.table.limit || self.table.is_empty() {
+            return Ok(());
+        }
+
+        let (table, layout, n) = (&self.table, &self.layout, self.written);
+        self.written += 1;
+        let text = Fmt::new(|f| table.fmt_row(f, layout, n, &row));
+        table.write_text(&mut self.out, &mut self.started, text)?;
+        self.out.flush()
+    }
+
+    /// Returns the output
+    pub fn get_ref(&self) -> &W {
+        &self.out
+    }
+
+    /// Writes the bottom rule and returns the output
+    pub fn finish(mut self) -> io::Result<W> {
+        self.table
+            .write_line(&mut self.out, &mut self.started, &self.layout, Line::Bottom)?;
+        self.out.flush()?;
+        Ok(self.out)
+    }
+}
+
+/// Shortens the cell to the width with an ellipsis on the side
+/// opposite to the alignment, so right-aligned numbers keep their last digits.
+/// The ellipsis is dropped if it doesn't fit the width.
+fn truncate<'c>(cell: &'c str, width: usize, align: Align, ellipsis: &str) -> Cow<'c, str> {
+    let len = ansi::display_width(cell);
+    if len <= width {
+        return Cow::Borrowed(cell);
+    }
+
+    let marker = ansi::display_width(ellipsis);
+    let ellipsis = if marker <= width { ellipsis } else { "" };
+    let kept = width - ansi::display_width(ellipsis);
+
+    let mut truncated = String::new();
+    match align {
+        Align::Left => {
+            truncated.extend(cell.chars().take(kept));
+            truncated.push_str(ellipsis);
+        }
+        Align::Right => {
+            truncated.push_str(ellipsis);
+            truncated.extend(cell.chars().skip(len - kept));
+        }
+        Align::Center => {
+            // Both sides lose the same number of chars
+            let (kept, right) = match kept.checked_sub(marker) {
+                Some(kept) => (kept, ellipsis),
+                None => (kept, ""),
+            };
+
+            let skip = (len - kept) / 2;
+            truncated.push_str(ellipsis);
+            truncated.extend(cell.chars().skip(skip).take(kept));
+            truncated.push_str(right);
+        }
+    }
+
+    Cow::Owned(truncated)
+}
+
+/// Pads the cell with the fill by its width without escape sequences.
+/// Without `trailing` the fill after the cell is omitted.
+fn write_aligned(
+    f: &mut fmt::Formatter,
+    cell: &str,
+    width: usize,
+    align: Align,
+    fill: char,
+    trailing: bool,
+) -> fmt::Result {
+    let pad = width.saturating_sub(ansi::display_width(cell));
+    let left = match align {
+        Align::Left => 0,
+        Align::Right => pad,
+        Align::Center => pad / 2,
+    };
+
+    write_fill(f, fill, left)?;
+    write!(f, "{}", cell)?;
+    if trailing {
+        write_fill(f, fill, pad - left)?;
+    }
+
+    Ok(())
+}
+
+fn write_fill(f: &mut fmt::Formatter, fill: char, len: usize) -> fmt::Result {
+    for _ in 0..len {
+        write!(f, "{}", fill)?;
+    }
+    Ok(())
+}
+
+/// Sizes of the padding around cells.
+#[derive(Copy, Clone)]
+struct Pads {
+    left: usize,
+    right: usize,
+}
+
+impl Pads {
+    /// Returns pads of a cell, the sides without an outer border are not padded
+    fn new(padding: usize, first: bool, last: bool, left: &str, right: &str) -> Self {
+        Self {
+            left: if first && left.is_empty() { 0 } else { padding },
+            right: if last && right.is_empty() { 0 } else { padding },
+        }
+    }
+}
+
+/// Writes a line of cells padded to the column widths.
+/// Without an outer border the cell isn't padded on that side.
+/// Cells are aligned with the fill char.
+/// With `trim` the last cell isn't padded on the right.
+fn write_line<'c, C>(
+    f: &mut fmt::Formatter,
+    borders: &Borders,
+    cells: C,
+    widths: &[usize],
+    padding: usize,
+    fill: char,
+    trim: bool,
+) -> fmt::Result
+where
+    C: IntoIterator<Item = (Cow<'c, str>, Align)>,
+{
+    write!(f, "{}", borders.left)?;
+    for (idx, ((cell, align), &width)) in cells.into_iter().zip(widths).enumerate() {
+        let first = idx == 0;
+        let last = idx + 1 == widths.len();
+        if !first {
+            write!(f, "{}", borders.inner)?;
+        }
+
+        let pads = Pads::new(padding, first, last, borders.left, borders.right);
+        write_fill(f, ' ', pads.left)?;
+
+        // Don't leave trailing whitespace
+        let trim = last && (trim || borders.right.is_empty() && align == Align::Left);
+        write_aligned(f, &cell, width, align, fill, !trim)?;
+        if !trim {
+            write_fill(f, ' ', pads.right)?;
+        }
+    }
+    write!(f, "{}", borders.right)
+}
+
+/// Writes a horizontal rule. If `aligns` is set,
+/// the alignment of columns is marked with colons like in Markdown.
+fn write_rule(
+    f: &mut fmt::Formatter,
+    rule: &Rule,
+    widths: &[usize],
+    padding: usize,
+    aligns: Option<&[Align]>,
+) -> fmt::Result {
+    write!(f, "{}", rule.left)?;
+    for (idx, &width) in widths.iter().enumerate() {
+        let first = idx == 0;
+        let last = idx + 1 == widths.len();
+        if !first {
+            write!(f, "{}", rule.cross)?;
+        }
+
+        // The rule spans the cell with its padding
+        let pads = Pads::new(padding, first, last, rule.left, rule.right);
+        let len = pads.left + width + pads.right;
+        if len == 0 {
+            continue;
+        }
+
+        let (left, right) = match aligns.map(|aligns| aligns[idx]) {
+            Some(Align::Right) => (rule.fill, ':'),
+            Some(Align::Center) => (':', ':'),
+            Some(Align::Left) | None => (rule.fill, rule.fill),
+        };
+
+        write!(f, "{}", left)?;
+        write_fill(f, rule.fill, len.saturating_sub(2))?;
+        if len > 1 {
+            write!(f, "{}", right)?;
+        }
+    }
+    write!(f, "{}", rule.right)
+}
+
+fn write_html_row(f: &mut fmt::Formatter, tag: &str, cells: &[Cow<str>]) -> fmt::Result {
+    write!(f, "    <tr>")?;
+    for cell in cells {
+        write!(f, "<{}>{}</{}>", tag, escape_html(cell), tag)?;
+    }
+    writeln!(f, "</tr>")
+}
+
+fn write_csv_row(f: &mut fmt::Formatter, cells: &[Cow<str>]) -> fmt::Result {
+    for (idx, cell) in cells.iter().enumerate() {
+        if idx != 0 {
+            write!(f, ",")?;
+        }
+
+        if cell.contains(&[',', '"', '\n', '\r'][..]) {
+            write!(f, "\"{}\"", cell.replace('"', "\"\""))?;
+        } else {
+            write!(f, "{}", cell)?;
+        }
+    }
+    writeln!(f)
+}
+
+fn write_jira_row(f: &mut fmt::Formatter, delimiter: &str, cells: &[Cow<str>]) -> fmt::Result {
+    write!(f, "{}", delimiter)?;
+    for cell in cells {
+        // An empty cell would merge the delimiters into the header one
+        let cell = if cell.is_empty() { " " } else { cell };
+        write!(f, "{}{}", cell.replace('|', "\\|"), delimiter)?;
+    }
+    writeln!(f)
+}
+
+fn write_json_array(f: &mut fmt::Formatter, cells: &[Cow<str>]) -> fmt::Result {
+    write!(f, "[")?;
+    for (idx, cell) in cells.iter().enumerate() {
+        if idx != 0 {
+            write!(f, ", ")?;
+        }
+
+        write!(f, "\"")?;
+        for ch in cell.chars() {
+            match ch {
+                '"' => write!(f, "\\\"")?,
+                '\\' => write!(f, "\\\\")?,
+                '\n' => write!(f, "\\n")?,
+                '\r' => write!(f, "\\r")?,
+                '\t' => write!(f, "\\t")?,
+                ch if ch.is_control() => write!(f, "\\u{:04x}", ch as u32)?,
+                _ => write!(f, "{}", ch)?,
+            }
+        }
+        write!(f, "\"")?;
+    }
+    write!(f, "]")
+}
+
+impl<'a> Table<'a> {
+    /// Truncates and paints cells of the row and pairs them with alignments of their columns
+    fn aligned<'t>(
+        &'t self,
+        layout: &'t Layout,
+        index: usize,
+        row: &'t [Cow<str>],
+        kind: Row,
+    ) -> impl Iterator<Item = (Cow<'t, str>, Align)> + 't {
+        row.iter().enumerate().map(move |(col, cell)| {
+            let align = self.cell_align(index, col);
+            let cell = match kind {
+                Row::Header => cell,
+                Row::Data | Row::Highlighted => self.shown_cell(col, cell),
+            };
+
+            let cell = match (layout.decimals[col], self.tab_width) {
+                (Some(decimal), _) if kind != Row::Header && self.is_number(cell) => {
+                    Cow::Owned(decimal.pad(cell, self.decimal_separator))
+                }
+                (_, Some(tab_width)) => expand_tabs(cell, tab_width),
+                (_, None) => Cow::Borrowed(cell),
+            };
+
+            let cell = if self.metrics().nbsp {
+                match encode_spaces(&cell) {
+                    Cow::Borrowed(_) => cell,
+                    Cow::Owned(encoded) => Cow::Owned(encoded),
+                }
+            } else {
+                cell
+            };
+
+            let cell = match truncate(&cell, layout.col_width(col), align, &self.ellipsis) {
+                Cow::Borrowed(_) => cell,
+                Cow::Owned(truncated) => Cow::Owned(truncated),
+            };
+
+            let color = self.col_colors.get(col).copied().flatten();
+            let style = match (kind, color) {
+                (Row::Header, _) => self.header_style,
+                (Row::Data, Some(color)) => Ansi::new().color(color),
+                (Row::Data, None) => Ansi::default(),
+                (Row::Highlighted, Some(color)) => Ansi::new().color(color).inverse(),
+                (Row::Highlighted, None) => Ansi::new().inverse(),
+            };
+
+            let cell = if style != Ansi::default() {
+                Cow::Owned(style.paint(&cell))
+            } else {
+                cell
+            };
+
+            (cell, align)
+        })
+    }
+
+    fn borders(&self) -> Borders {
+        let mut borders = self.style.borders();
+        if !self.outer_border {
+            borders.left = "";
+            borders.right = "";
+        }
+        borders
+    }
+
+    fn rule(&self, rule: Option<Rule>) -> Option<Rule> {
+        let mut rule = rule?;
+        if !self.outer_border {
+            rule.left = "";
+            rule.right = "";
+        }
+        Some(rule)
+    }
+
+    /// Returns lines of the text format in order
+    fn text_lines(&self) -> impl Iterator<Item = Line> {
+        let shown = self.shown();
+        let separator = shown != 0 && self.style.separator().is_some();
+
+        (0..self.comments.len())
+            .map(Line::Comment)
+            .chain(self.style.top().map(|_| Line::Top))
+            .chain(Some(Line::Header))
+            .chain(Some(Line::Separator).filter(|_| separator))
+            .chain((0..shown).map(Line::Row))
+            .chain(self.style.bottom().map(|_| Line::Bottom))
+    }
+
+    /// Measures columns to render `shown` data rows
+    fn layout(&self, shown: usize) -> Layout {
+        // The width of the row numbers column fits the largest number
+        let numbers_width = if self.row_numbers {
+            Some(shown.max(1).to_string().len())
+        } else {
+            None
+        };
+
+        let decimals: Vec<_> = (0..self.cols_len()).map(|col| self.decimal(col)).collect();
+
+        let widths: Vec<_> = numbers_width
+            .into_iter()
+            .chain(decimals.iter().enumerate().map(|(col, decimal)| {
+                match (self.fixed_widths.get(col), decimal) {
+                    (Some(&width), _) => width,
+                    // Padded numbers may be wider than any cell
+                    (None, Some(decimal)) => {
+                        let width = self.cols_width[col].max(decimal.int + decimal.frac);
+                        self.limit_width(col, width)
+                    }
+                    (None, None) => self.col_width(col),
+                }
+            }))
+            .collect();
+
+        Layout {
+            numbers: numbers_width.is_some(),
+            widths: self.fit_dashes(widths),
+            decimals,
+        }
+    }
+
+    /// Widens columns so the Markdown separator has enough dashes
+    fn fit_dashes(&self, mut widths: Vec<usize>) -> Vec<usize> {
+        if self.style != Style::Markdown {
+            return widths;
+        }
+
+        let borders = self.borders();
+        let len = widths.len();
+        for (idx, width) in widths.iter_mut().enumerate() {
+            let pads = Pads::new(
+                self.padding,
+                idx == 0,
+                idx + 1 == len,
+                borders.left,
+                borders.right,
+            );
+            *width = (*width).max(self.min_dashes.saturating_sub(pads.left + pads.right));
+        }
+
+        widths
+    }
+
+    /// Measures numbers of the column if it's aligned on the decimal point
+    fn decimal(&self, col: usize) -> Option<Decimal> {
+        if !self.is_decimal(col) {
+            return None;
+        }
+
+        let decimal = self
+            .rows
+            .chunks(self.cols_len())
+            .skip(1)
+            .map(|row| &row[col])
+            .filter(|cell| self.is_number(cell))
+            .fold(Decimal::default(), |decimal, cell| {
+                decimal.fit(cell, self.decimal_separator)
+            });
+
+        Some(decimal)
+    }
+
+    /// Writes a line of the text format without the line break
+    fn fmt_line(&self, f: &mut fmt::Formatter, layout: &Layout, line: Line) -> fmt::Result {
+        write!(f, "{}", self.prefix)?;
+        let widths = &layout.widths;
+        let cols_len = self.cols_len();
+        match line {
+            Line::Top => match self.rule(self.style.top()) {
+                Some(rule) => write_rule(f, &rule, widths, self.padding, None),
+                None => Ok(()),
+            },
+            Line::Header => {
+                let header = &self.rows[..cols_len];
+                let number = Some((Cow::Borrowed("#"), Align::Left)).filter(|_| layout.numbers);
+                let cells = number
+                    .into_iter()
+                    .chain(self.aligned(layout, 0, header, Row::Header));
+                write_line(
+                    f,
+                    &self.borders(),
+                    cells,
+                    widths,
+                    self.padding,
+                    ' ',
+                    self.trim_trailing,
+                )
+            }
+            Line::Separator => {
+                let rule = match self.rule(self.style.separator()) {
+                    Some(rule) => rule,
+                    None => return Ok(()),
+                };
+
+                // The number column keeps the plain rule
+                let aligns: Vec<_> = Some(Align::Left)
+                    .filter(|_| layout.numbers)
+                    .into_iter()
+                    .chain((0..cols_len).map(|col| self.col_align(col)))
+                    .collect();
+
+                let aligns = match self.style {
+                    Style::Markdown => Some(&aligns[..]),
+                    Style::Unicode | Style::Rounded | Style::Plain => None,
+                };
+
+                write_rule(f, &rule, widths, self.padding, aligns)
+            }
+            Line::Row(n) => {
+                let row = &self.rows[(n + 1) * cols_len..(n + 2) * cols_len];
+                self.fmt_row(f, layout, n, row)
+            }
+            Line::Bottom => match self.rule(self.style.bottom()) {
+                Some(rule) => write_rule(f, &rule, widths, self.padding, None),
+                None => Ok(()),
+            },
+            Line::Comment(n) => write!(f, "#{}", self.comments[n]),
+        }
+    }
+
+    /// Writes the `n`th data row without the line break
+    fn fmt_row(
+        &self,
+        f: &mut fmt::Formatter,
+        layout: &Layout,
+        n: usize,
+        row: &[Cow<str>],
+    ) -> fmt::Result {
+        let number =
+            Some((Cow::Owned((n + 1).to_string()), Align::Right)).filter(|_| layout.numbers);
+        let kind = if self.highlight == Some(n) {
+            Row::Highlighted
+        } else {
+            Row::Data
+        };
+
+        // Missing cells of a short row have no columns
+        let widths = match self.lens.get(n) {
+            Some(&len) if self.ragged => &layout.widths[..len + layout.numbers as usize],
+            _ => &layout.widths[..],
+        };
+
+        let cells = number
+            .into_iter()
+            .chain(self.aligned(layout, n + 1, row, kind));
+        write_line(
+            f,
+            &self.borders(),
+            cells,
+            widths,
+            self.padding,
+            self.pad_char,
+            self.trim_trailing,
+        )
+    }
+
+    fn fmt_text(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let fence = if self.code_fence {
+            let fence = "`".repeat(self.fence_len());
+            writeln!(f, "{}{}", self.prefix, fence)?;
+            Some(fence)
+        } else {
+            None
+        };
+
+        let layout = self.layout(self.shown());
+        for line in self.text_lines() {
+            self.fmt_line(f, &layout, line)?;
+            writeln!(f)?;
+        }
+
+        if let Some(fence) = fence {
+            writeln!(f, "{}{}", self.prefix, fence)?;
+        }
+
+        Ok(())
+    }
+
+    /// Returns the number of backticks of the code fence,
+    /// it's longer than any run of backticks in cells and comments
+    fn fence_len(&self) -> usize {
+        let longest = self
+            .rows
+            .iter()
+            .chain(&self.comments)
+            .flat_map(|text| text.split(|ch| ch != '`'))
+            .map(str::len)
+            .max()
+            .unwrap_or(0);
+
+        longest.max(2) + 1
+    }
+
+    /// Returns the width of lines of the text format in chars.
+    /// Lines may be shorter without the right border or with `trim_trailing`.
+    pub fn rendered_width(&self) -> usize {
+        if self.is_empty() {
+            return 0;
+        }
+
+        let layout = self.layout(self.shown());
+        let borders = self.borders();
+        let len = layout.widths.len();
+        let cells: usize = layout
+            .widths
+            .iter()
+            .enumerate()
+            .map(|(idx, width)| {
+                let pads = Pads::new(
+                    self.padding,
+                    idx == 0,
+                    idx + 1 == len,
+                    borders.left,
+                    borders.right,
+                );
+                pads.left + width + pads.right
+            })
+            .sum();
+
+        let inner = borders.inner.chars().count() * len.saturating_sub(1);
+        let borders = borders.left.chars().count() + inner + borders.right.chars().count();
+        ansi::display_width(&self.prefix) + cells + borders
+    }
+
+    /// Renders the table with one shown data row as `a=1, b=2` pairs of the header
+    /// and the row, other tables are rendered as usual
+    pub fn to_inline(&self) -> String {
+        let row = match self.shown_rows().next() {
+            Some(row) if self.shown() == 1 && self.cols_len() != 0 => row,
+            _ => return self.to_string(),
+        };
+
+        let pairs: Vec<_> = self.rows[..self.cols_len()]
+            .iter()
+            .zip(row)
+            .map(|(label, cell)| format!("{}={}", label, cell))
+            .collect();
+
+        pairs.join(", ")
+    }
+
+    /// Starts writing the text format row by row. Column widths account for
+    /// rows passed to [`Table::measure`], data rows of the table itself aren't written.
+    /// Numbers aren't aligned on the decimal point since they aren't stored.
+    pub fn stream(&self) -> Stream<'_, 'a> {
+        let shown = match self.limit {
+            Some(limit) => limit.min(self.measured),
+            None => self.measured,
+        };
+
+        Stream {
+            table: self,
+            layout: self.layout(shown),
+            shown,
+            written: 0,
+            started: false,
+        }
+    }
+
+    /// Renders only the header and the separator of the text format,
+    /// columns are as wide as with data rows
+    pub fn render_header_only(&self) -> String {
+        if self.is_empty() {
+            return String::new();
+        }
+
+        let layout = self.layout(self.shown());
+        let separator = self.style.separator().map(|_| Line::Separator);
+        let lines = Some(Line::Header).into_iter().chain(separator);
+        let output = Fmt::new(|f| {
+            for line in lines.clone() {
+                self.fmt_line(f, &layout, line)?;
+                writeln!(f)?;
+            }
+
+            Ok(())
+        });
+
+        output.to_string()
+    }
+
+    /// Writes the comments, the header and the separator of the text format and
+    /// returns the writer of data rows. Row numbers are as wide as the limit.
+    pub fn writer<W>(self, mut out: W) -> io::Result<TableWriter<'a, W>>
+    where
+        W: io::Write,
+    {
+        let layout = self.layout(self.limit.unwrap_or(0));
+        let mut started = false;
+        let comments = (0..self.comments.len()).map(Line::Comment);
+        for line in comments.chain([Line::Top, Line::Header, Line::Separator]) {
+            self.write_line(&mut out, &mut started, &layout, line)?;
+        }
+
+        out.flush()?;
+        Ok(TableWriter {
+            table: self,
+            layout,
+            out,
+            written: 0,
+            started,
+        })
+    }
+
+    /// Writes the line of the text format if the `Display` output would have it
+    fn write_line<W>(
+        &self,
+        out: &mut W,
+        started: &mut bool,
+        layout: &Layout,
+        line: Line,
+    ) -> io::Result<()>
+    where
+        W: io::Write,
+    {
+        let rule = match line {
+            Line::Top => self.style.top(),
+            Line::Separator => self.style.separator(),
+            Line::Bottom => self.style.bottom(),
+            Line::Comment(_) | Line::Header | Line::Row(_) => None,
+        };
+
+        let text = matches!(line, Line::Comment(_) | Line::Header);
+        if self.is_empty() || !text && rule.is_none() {
+            return Ok(());
+        }
+
+        let text = Fmt::new(|f| self.fmt_line(f, layout, line));
+        self.write_text(out, started, text)
+    }
+
+    /// Iterates over rendered lines without line breaks and the byte order mark,
+    /// same as `Display` output. Lines of the text format are rendered lazily.
+    pub fn lines(&self) -> impl Iterator<Item = String> + '_ {
+        let text = match self.format {
+            Format::Text if !self.is_empty() => Some(self.layout(self.shown())),
+            _ => None,
+        };
+
+        let other: Vec<_> = match text {
+            Some(_) => vec![],
+            None if self.is_empty() => vec![],
+            None => Fmt::new(|f| self.fmt_output(f))
+                .to_string()
+                .lines()
+                .map(String::from)
+                .collect(),
+        };
+
+        let fence = match text {
+            Some(_) if self.code_fence => {
+                let fence = "`".repeat(self.fence_len());
+                Some(format!("{}{}", self.prefix, fence))
+            }
+            _ => None,
+        };
+
+        let text = text.map(|layout| {
+            self.text_lines()
+                .map(move |line| Fmt::new(|f| self.fmt_line(f, &layout, line)).to_string())
+        });
+
+        fence
+            .clone()
+            .into_iter()
+            .chain(text.into_iter().flatten())
+            .chain(fence)
+            .chain(other)
+    }
+
+    /// Renders the table in its format with `\n` line breaks
+    fn fmt_output(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.format {
+            Format::Text => self.fmt_text(f),
+            Format::Html => self.fmt_html(f),
+            Format::Csv => self.fmt_csv(f),
+            Format::Json => self.fmt_json(f),
+            Format::Jira => self.fmt_jira(f),
+        }
+    }
+
+    /// Passes the formatter to `write`, line breaks become `\r\n` if `crlf` is enabled
+    fn with_line_ending<F>(&self, f: &mut fmt::Formatter, write: F) -> fmt::Result
+    where
+        F: FnOnce(&mut dyn fmt::Write) -> fmt::Result,
+    {
+        if self.crlf {
+            write(&mut Crlf(f))
+        } else {
+            write(f)
+        }
+    }
+
+    /// Writes the line with the line ending and the byte order mark of `Display` output.
+    /// Without the trailing line break, the line break is written before the next line.
+    fn write_text<W, T>(&self, out: &mut W, started: &mut bool, text: T) -> io::Result<()>
+    where
+        W: io::Write,
+        T: fmt::Display,
+    {
+        let first = !*started;
+        *started = true;
+        let bom = if first && self.bom { "\u{feff}" } else { "" };
+        let (start, end) = match (first, self.trailing_newline) {
+            (_, true) => ("", "\n"),
+            (true, false) => ("", ""),
+            (false, false) => ("\n", ""),
+        };
+
+        let line = Fmt::new(|f| {
+            f.write_str(bom)?;
+            self.with_line_ending(f, |out| {
+                out.write_fmt(format_args!("{}{}{}", start, text, end))
+            })
+        });
+
+        write!(out, "{}", line)
+    }
+
+    fn fmt_html(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "<table>")?;
+        writeln!(f, "  <thead>")?;
+        write_html_row(f, "th", &self.rows[..self.cols_len()])?;
+        writeln!(f, "  </thead>")?;
+
+        if self.shown() != 0 {
+            writeln!(f, "  <tbody>")?;
+            for row in self.shown_rows() {
+                write_html_row(f, "td", row)?;
+            }
+            writeln!(f, "  </tbody>")?;
+        }
+
+        writeln!(f, "</table>")
+    }
+
+    fn fmt_csv(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write_csv_row(f, &self.rows[..self.cols_len()])?;
+        for row in self.shown_rows() {
+            write_csv_row(f, row)?;
+        }
+
+        Ok(())
+    }
+
+    fn fmt_jira(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write_jira_row(f, "||", &self.rows[..self.cols_len()])?;
+        for row in self.shown_rows() {
+            write_jira_row(f, "|", row)?;
+        }
+
+        Ok(())
+    }
+
+    fn fmt_json(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "{{")?;
+        write!(f, "  \"header\": ")?;
+        write_json_array(f, &self.rows[..self.cols_len()])?;
+        writeln!(f, ",")?;
+        write!(f, "  \"rows\": [")?;
+        for (idx, row) in self.shown_rows().enumerate() {
+            if idx != 0 {
+                write!(f, ",")?;
+            }
+
+            write!(f, "\n    ")?;
+            write_json_array(f, row)?;
+        }
+
+        if self.shown() != 0 {
+            write!(f, "\n  ")?;
+        }
+        writeln!(f, "]")?;
+        writeln!(f, "}}")
+    }
+}
+
+/// Renders the table in its format, an empty table renders as an empty string
+impl fmt::Display for Table<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.is_empty() {
+            return Ok(());
+        }
+
+        if self.bom {
+            f.write_str("\u{feff}")?;
+        }
+
+        let output = Fmt::new(|f| self.fmt_output(f));
+        self.with_line_ending(f, |out| {
+            if self.trailing_newline {
+                out.write_fmt(format_args!("{}", output))
+            } else {
+                let mut trim = TrimNewline {
+                    out,
+                    pending: false,
+                };
+                fmt::Write::write_fmt(&mut trim, format_args!("{}", output))
+            }
+        })
+    }
+}
@@ -0,0 +1,69 @@
+use std::{
+    io::Write,
+    process::{Command, Stdio},
+};
+
+/// Runs the binary with the input and returns its output
+fn run(args: &[&str], input: &str) -> String {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_tableflip-rust"))
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .expect("run the binary");
+
+    let mut stdin = child.stdin.take().unwrap();
+    stdin.write_all(input.as_bytes()).unwrap();
+    drop(stdin);
+
+    let output = child.wait_with_output().unwrap();
+    assert!(output.status.success());
+    String::from_utf8(output.stdout).unwrap()
+}
+
+#[test]
+fn format_csv() {
+    let output = run(&["--format", "csv"], "\"a\" \"b\"\n\"1\" \"2, 3\"\n");
+    assert_eq!(output, "a,b\n1,\"2, 3\"\n");
+}
+
+#[test]
+fn headers() {
+    let output = run(&["--headers", "name,n"], "\"a\" \"1\"\n\"b\" \"2\"\n");
+    assert_eq!(
+        output,
+        "\
+        | name | n |\n\
+        |------|---|\n\
+        | a    | 1 |\n\
+        | b    | 2 |\n\
+        "
+    );
+}
+
+#[test]
+fn blank_header() {
+    let output = run(&[], "\n\"a\"\n");
+    assert_eq!(output, "");
+}
+
+#[test]
+fn comments() {
+    let output = run(&["--comments"], "# generated\n\"a\"\n\"0\"\n");
+    assert_eq!(output, "# generated\n| a |\n|---|\n| 0 |\n");
+}
+
+#[test]
+fn stream() {
+    let input = "\"a\" \"b\"\n\"1\" \"multi\nline\"\n\n\"2\"\n\"3\" \"4\" \"5\"\n";
+    let name = format!("tableflip-stream-{}.txt", std::process::id());
+    let path = std::env::temp_dir().join(name);
+    std::fs::write(&path, input).unwrap();
+
+    let file = path.to_str().unwrap();
+    let output = run(&["--stream", "--style", "unicode", file], "");
+    let plain = run(&["--style", "unicode", file], "");
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(output, plain);
+    assert_eq!(output, run(&["--style", "unicode"], input));
+}